@@ -26,10 +26,8 @@ async fn exex<Node: FullNodeComponents>(mut ctx: ExExContext<Node>) -> eyre::Res
                 info!(committed_chain = ?new.range(), "Received commit");
                 let block = new.block(new.tip().block.hash()).unwrap();
                 let block_number = block.number;
-                let transaction_length = block.body.transactions.len() as u64;
                 let mut client = DataProcessorClient::new();
                 client.write(block_number);
-                client.write(transaction_length);
                 let (proof, vk) = client.prove("../program".into()).unwrap();
                 client.verify(&proof, &vk).expect("failed to verify proof");
             }
@@ -29,9 +29,7 @@ pub fn main() {
     // ===============================================
 
     let block_number: u64 = hdp::read();
-    let transaction_length: u64 = hdp::read();
     println!("Received block_number: {:?}", block_number);
-    println!("Received transaction_length: {:?}", transaction_length);
 
     // ================================================
     // Initialize Bloom Filter
@@ -53,13 +51,19 @@ pub fn main() {
 
     let _ = memorizer.get_header(header_key).unwrap();
 
-    for i in 0..transaction_length {
-        let tx_key = TransactionKey {
-            block_number: 5244652,
-            transaction_index: i,
-            ..Default::default()
-        };
-        let tx: TxEnvelope = memorizer.get_transaction(tx_key).unwrap();
+    // The transaction count is proven, so no transaction of the block can be skipped.
+    let tx_count_key = TransactionCountKey {
+        block_number: 5244652,
+        ..Default::default()
+    };
+    let txs: Vec<TxEnvelope> = memorizer
+        .get_block_transactions(tx_count_key)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    println!("Verified transaction count: {:?}", txs.len());
+
+    for tx in txs {
         let signer = tx.recover_signer().unwrap();
         println!("recover signer: {:?}", signer);
         println!("cycle-tracker-start: bloom check");
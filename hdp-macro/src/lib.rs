@@ -2,11 +2,22 @@ use darling::ast::NestedMeta;
 use darling::{Error, FromMeta};
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, ItemFn};
+use std::{env, fs, path::PathBuf};
+use syn::{parse_macro_input, ItemFn, ItemStruct};
+
+mod storage_layout;
 
 #[derive(Debug, FromMeta)]
 struct MacroArgs {
     to_chain_id: String,
+    /// Hash function of the MMR the headers are proven against, `keccak` if unset.
+    #[darling(default)]
+    hashing_function: Option<String>,
+}
+
+#[derive(Debug, FromMeta)]
+struct StorageLayoutArgs {
+    path: String,
 }
 
 #[proc_macro_attribute]
@@ -25,6 +36,22 @@ pub fn hdp_main(args: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
+    let hasher = match args.hashing_function.as_deref() {
+        None | Some("keccak") => quote! { hdp_lib::mmr::KeccakHasher },
+        Some("poseidon") => quote! { hdp_lib::mmr::PoseidonHasher },
+        Some(other) => {
+            return TokenStream::from(
+                syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    format!(
+                        "unsupported hashing function `{other}`, expected `keccak` or `poseidon`"
+                    ),
+                )
+                .to_compile_error(),
+            );
+        }
+    };
+
     let input_fn = parse_macro_input!(item as ItemFn);
 
     let fn_vis = &input_fn.vis;
@@ -87,11 +114,11 @@ pub fn hdp_main(args: TokenStream, item: TokenStream) -> TokenStream {
                     println!("Hello, world! from zkvm");
 
                     // Read an input to the program.
-                    let mut memorizer = sp1_zkvm::io::read::<Memorizer>();
+                    let mut memorizer = sp1_zkvm::io::read::<Memorizer<#hasher>>();
                 } else {
                     println!("Hello, world! from online mode");
                     let chain_map = get_rpc_urls();
-                    let mut memorizer = Memorizer::new(chain_map, #to_chain_id);
+                    let mut memorizer = Memorizer::<#hasher>::with_hasher(chain_map, #to_chain_id);
                 }
             }
 
@@ -115,7 +142,12 @@ pub fn hdp_main(args: TokenStream, item: TokenStream) -> TokenStream {
             cfg_if! {
                 if #[cfg(target_os = "zkvm")] {
                     if let Some(result_value) = hdp_commit_value {
-                        let mmr_meta = memorizer.mmr_meta.get(&ChainId::from_str(#to_chain_id).unwrap()).expect("MMR metadata not found");
+                        let chain_id = ChainId::from_str(#to_chain_id).unwrap();
+                        // A newer snapshot proven consistent with the memorized one is committed instead.
+                        let mmr_meta = match memorizer.newer_mmr_meta.get(&chain_id) {
+                            Some((newer, true)) => &newer.meta,
+                            _ => memorizer.mmr_meta.get(&chain_id).expect("MMR metadata not found"),
+                        };
 
                         let public_values = PublicValuesStruct {
                             mmrId: mmr_meta.mmr_id,
@@ -144,3 +176,109 @@ pub fn hdp_main(args: TokenStream, item: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Generates a typed storage accessor struct from a `solc` storage layout JSON.
+///
+/// The `path` is relative to the manifest directory of the calling crate and points to the
+/// `storageLayout` output of `solc`. Every state variable gets an accessor that computes its slot:
+/// value types are read through `get_storage` and decoded, while other types return their slot.
+///
+/// ```
+/// use alloy_primitives::{address, Address, B256, U256};
+/// use hdp_lib::memorizer::{
+///     MemorizerError, StorageChange, StorageChangeKey, StorageKey, StorageMemorizer, StoragesKey,
+/// };
+/// use hdp_lib::storage_layout::StorageSlot;
+/// use hdp_macro::storage_layout;
+/// use std::collections::HashMap;
+///
+/// #[storage_layout(path = "layouts/Token.json")]
+/// pub struct Token;
+///
+/// /// Storage of a single contract, read without proofs.
+/// struct Storage(HashMap<B256, U256>);
+///
+/// impl StorageMemorizer for Storage {
+///     fn get_storage(&mut self, key: StorageKey) -> Result<U256, MemorizerError> {
+///         Ok(self.0.get(&key.storage_slot).copied().unwrap_or_default())
+///     }
+///
+///     fn get_storages(&mut self, key: StoragesKey) -> Result<Vec<U256>, MemorizerError> {
+///         key.storage_keys().map(|key| self.get_storage(key)).collect()
+///     }
+///
+///     // The storage never changes.
+///     fn get_storage_change(
+///         &mut self,
+///         _key: StorageChangeKey,
+///     ) -> Result<Option<StorageChange>, MemorizerError> {
+///         Ok(None)
+///     }
+/// }
+///
+/// let holder = address!("75cec1db9dceb703200eaa6595f66885c962b920");
+/// let mut storage = Storage(HashMap::from([
+///     // `owner` and `paused` share slot 3
+///     (
+///         B256::from(StorageSlot::new(3)),
+///         U256::from_be_slice(&[&[1u8][..], holder.as_slice()].concat()),
+///     ),
+///     (B256::from(StorageSlot::new(0).mapping(&holder)), U256::from(1000)),
+/// ]));
+///
+/// let token = Token::at(Address::ZERO, 20_000_000);
+/// assert_eq!(token.owner(&mut storage)?, holder);
+/// assert!(token.paused(&mut storage)?);
+/// assert_eq!(token.balances(&mut storage, holder)?, U256::from(1000));
+/// assert_eq!(token.name_slot(), StorageSlot::new(4));
+/// # Ok::<(), MemorizerError>(())
+/// ```
+#[proc_macro_attribute]
+pub fn storage_layout(args: TokenStream, item: TokenStream) -> TokenStream {
+    let attr_args = match NestedMeta::parse_meta_list(args.into()) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(Error::from(e).write_errors());
+        }
+    };
+
+    let args = match StorageLayoutArgs::from_list(&attr_args) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(e.write_errors());
+        }
+    };
+
+    let input_struct = parse_macro_input!(item as ItemStruct);
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
+    let path = PathBuf::from(manifest_dir).join(&args.path);
+    let layout = fs::read_to_string(&path)
+        .map_err(|e| format!("failed to read storage layout {path:?}: {e}"))
+        .and_then(|json| {
+            serde_json::from_str::<storage_layout::StorageLayout>(&json)
+                .map_err(|e| format!("invalid storage layout {path:?}: {e}"))
+        });
+    let layout = match layout {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(
+                syn::Error::new(proc_macro2::Span::call_site(), e).to_compile_error(),
+            );
+        }
+    };
+
+    let expanded = match storage_layout::expand(&input_struct, &layout) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(e.to_compile_error());
+        }
+    };
+
+    // Rebuild whenever the layout file changes.
+    let path = path.to_string_lossy().into_owned();
+    TokenStream::from(quote! {
+        const _: &[u8] = include_bytes!(#path);
+        #expanded
+    })
+}
@@ -0,0 +1,422 @@
+use alloy_primitives::U256;
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use syn::{Ident, ItemStruct};
+
+/// The `storageLayout` output of `solc`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct StorageLayout {
+    storage: Vec<StorageEntry>,
+    #[serde(default)]
+    types: HashMap<String, TypeEntry>,
+}
+
+/// A state variable, or a struct member, of a storage layout.
+#[derive(Debug, Deserialize)]
+struct StorageEntry {
+    label: String,
+    offset: usize,
+    slot: String,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+/// A type referenced by a storage layout.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeEntry {
+    encoding: String,
+    label: String,
+    number_of_bytes: String,
+    key: Option<String>,
+    value: Option<String>,
+}
+
+/// How the value of a state variable is read.
+enum Access {
+    /// A value type packed in a single slot, decoded into the given Rust type.
+    Value { ty: TokenStream, size: usize },
+    /// Any other type, for which only the slot is exposed.
+    Slot,
+}
+
+/// Generates the accessor struct for `item` from a `solc` storage layout.
+pub(crate) fn expand(item: &ItemStruct, layout: &StorageLayout) -> syn::Result<TokenStream> {
+    let attrs = &item.attrs;
+    let vis = &item.vis;
+    let name = &item.ident;
+
+    let mut seen = HashSet::new();
+    let mut accessors = Vec::new();
+    for entry in &layout.storage {
+        let fn_name = accessor_name(&entry.label);
+        if fn_name.is_empty() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                format!("no accessor name for state variable `{}`", entry.label),
+            ));
+        }
+        if !seen.insert(fn_name.clone()) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                format!(
+                    "duplicate accessor `{fn_name}` for state variable `{}`",
+                    entry.label
+                ),
+            ));
+        }
+        accessors.push(expand_accessor(layout, entry, &fn_name)?);
+    }
+
+    Ok(quote! {
+        #(#attrs)*
+        #[derive(Debug, Clone, Copy)]
+        #vis struct #name {
+            /// Chain ID of the network.
+            pub chain_id: ::hdp_lib::chain::ChainId,
+            /// Block number at which the storage is read.
+            pub block_number: u64,
+            /// Address of the contract.
+            pub address: ::alloy_primitives::Address,
+        }
+
+        impl #name {
+            /// Reads the storage of the contract at `address`, as of `block_number`.
+            pub fn at(address: ::alloy_primitives::Address, block_number: u64) -> Self {
+                Self {
+                    chain_id: ::core::default::Default::default(),
+                    block_number,
+                    address,
+                }
+            }
+
+            /// Reads the storage on the chain `chain_id`.
+            pub fn on(mut self, chain_id: ::hdp_lib::chain::ChainId) -> Self {
+                self.chain_id = chain_id;
+                self
+            }
+
+            #(#accessors)*
+        }
+    })
+}
+
+/// Generates the accessor of a single state variable.
+fn expand_accessor(
+    layout: &StorageLayout,
+    entry: &StorageEntry,
+    fn_name: &str,
+) -> syn::Result<TokenStream> {
+    let label = &entry.label;
+    let slot = parse_slot(&entry.slot)?;
+    let limbs = slot.as_limbs();
+
+    // Every mapping level adds a key argument.
+    let mut ty = lookup(layout, &entry.ty)?;
+    let mut key_args = Vec::new();
+    let mut key_names = Vec::new();
+    while ty.encoding == "mapping" {
+        let key_ty = lookup(layout, ty.key.as_deref().unwrap_or_default())?;
+        let arg = format_ident!("key{}", key_args.len());
+        let rust_ty = mapping_key_type(key_ty)?;
+        key_args.push(quote! { #arg: #rust_ty });
+        key_names.push(arg);
+        ty = lookup(layout, ty.value.as_deref().unwrap_or_default())?;
+    }
+
+    let slot_expr = quote! {
+        ::hdp_lib::storage_layout::StorageSlot(
+            ::alloy_primitives::U256::from_limbs([#(#limbs),*])
+        )
+        #(.mapping(&#key_names))*
+    };
+
+    let offset = entry.offset;
+    match access(ty) {
+        Access::Value { ty: value_ty, size } => {
+            let fn_ident = accessor_ident(fn_name)?;
+            let doc = format!("Reads `{label}`, of type `{}`.", ty.label);
+            Ok(quote! {
+                #[doc = #doc]
+                pub fn #fn_ident<M: ::hdp_lib::memorizer::StorageMemorizer>(
+                    &self,
+                    memorizer: &mut M,
+                    #(#key_args),*
+                ) -> ::core::result::Result<#value_ty, ::hdp_lib::memorizer::MemorizerError> {
+                    #slot_expr.packed(#offset, #size).read(
+                        memorizer,
+                        self.chain_id,
+                        self.block_number,
+                        self.address,
+                    )
+                }
+            })
+        }
+        Access::Slot => {
+            let fn_ident = accessor_ident(&format!("{fn_name}_slot"))?;
+            let doc = format!("Returns the slot of `{label}`, of type `{}`.", ty.label);
+            Ok(quote! {
+                #[doc = #doc]
+                pub fn #fn_ident(&self, #(#key_args),*) -> ::hdp_lib::storage_layout::StorageSlot {
+                    #slot_expr
+                }
+            })
+        }
+    }
+}
+
+/// Returns the definition of the type `id`.
+fn lookup<'a>(layout: &'a StorageLayout, id: &str) -> syn::Result<&'a TypeEntry> {
+    layout.types.get(id).ok_or_else(|| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("type `{id}` is missing from the storage layout"),
+        )
+    })
+}
+
+/// Parses a decimal slot number.
+fn parse_slot(slot: &str) -> syn::Result<U256> {
+    U256::from_str_radix(slot, 10)
+        .map_err(|e| syn::Error::new(Span::call_site(), format!("invalid slot `{slot}`: {e}")))
+}
+
+/// Returns how a value of type `ty` is read.
+///
+/// Signed integers and fixed-size byte arrays shorter than 32 bytes are read as their raw bits.
+fn access(ty: &TypeEntry) -> Access {
+    if ty.encoding != "inplace" {
+        return Access::Slot;
+    }
+    let Ok(size) = ty.number_of_bytes.parse::<usize>() else {
+        return Access::Slot;
+    };
+    let label = ty.label.as_str();
+    let rust_ty = if label == "bool" {
+        quote! { bool }
+    } else if label == "address" || label == "address payable" || label.starts_with("contract ") {
+        quote! { ::alloy_primitives::Address }
+    } else if label == "bytes32" {
+        quote! { ::alloy_primitives::B256 }
+    } else if label.starts_with("enum ") {
+        quote! { u8 }
+    } else if label.starts_with("uint") {
+        uint_type(size)
+    } else if label.starts_with("int") {
+        int_type(size)
+    } else if label.starts_with("bytes") && label != "bytes" {
+        fixed_bytes_type(size)
+    } else if size <= 32 && !label.starts_with("struct ") && !label.contains('[') {
+        quote! { ::alloy_primitives::U256 }
+    } else {
+        return Access::Slot;
+    };
+    Access::Value { ty: rust_ty, size }
+}
+
+/// Returns the Rust type of a mapping key of type `ty`.
+fn mapping_key_type(ty: &TypeEntry) -> syn::Result<TokenStream> {
+    let label = ty.label.as_str();
+    Ok(match label {
+        "address" | "address payable" => quote! { ::alloy_primitives::Address },
+        "bool" => quote! { bool },
+        "bytes32" => quote! { ::alloy_primitives::B256 },
+        "string" => quote! { &str },
+        "bytes" => quote! { &[u8] },
+        _ if label.starts_with("uint") || label.starts_with("enum ") => {
+            quote! { ::alloy_primitives::U256 }
+        }
+        _ if label.starts_with("int") => int_type(parse_size(ty)?),
+        _ if label.starts_with("bytes") => fixed_bytes_type(parse_size(ty)?),
+        _ if label.starts_with("contract ") => quote! { ::alloy_primitives::Address },
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                format!("unsupported mapping key type `{label}`"),
+            ))
+        }
+    })
+}
+
+/// Returns the Rust type of an unsigned integer of `size` bytes.
+fn uint_type(size: usize) -> TokenStream {
+    match size {
+        1 => quote! { u8 },
+        2 => quote! { u16 },
+        3..=4 => quote! { u32 },
+        5..=8 => quote! { u64 },
+        9..=16 => quote! { u128 },
+        _ => quote! { ::alloy_primitives::U256 },
+    }
+}
+
+/// Returns the Rust type of a signed integer of `size` bytes.
+fn int_type(size: usize) -> TokenStream {
+    match size {
+        1 => quote! { i8 },
+        2 => quote! { i16 },
+        3..=4 => quote! { i32 },
+        5..=8 => quote! { i64 },
+        9..=16 => quote! { i128 },
+        _ => quote! { ::alloy_primitives::I256 },
+    }
+}
+
+/// Returns the Rust type of a `bytesN` of `size` bytes.
+fn fixed_bytes_type(size: usize) -> TokenStream {
+    let size = Literal::usize_unsuffixed(size);
+    quote! { ::alloy_primitives::FixedBytes<#size> }
+}
+
+/// Parses the size in bytes of the type `ty`.
+fn parse_size(ty: &TypeEntry) -> syn::Result<usize> {
+    ty.number_of_bytes.parse().map_err(|e| {
+        syn::Error::new(
+            Span::call_site(),
+            format!(
+                "invalid size `{}` of type `{}`: {e}",
+                ty.number_of_bytes, ty.label
+            ),
+        )
+    })
+}
+
+/// Creates the identifier of an accessor, as a raw identifier if `name` is a Rust keyword.
+fn accessor_ident(name: &str) -> syn::Result<Ident> {
+    if matches!(name, "self" | "Self" | "super" | "crate") {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            format!("accessor name `{name}` is a reserved Rust keyword"),
+        ));
+    }
+    syn::parse_str::<Ident>(name)
+        .or_else(|_| syn::parse_str::<Ident>(&format!("r#{name}")))
+        .map_err(|_| syn::Error::new(Span::call_site(), format!("invalid accessor name `{name}`")))
+}
+
+/// Converts a Solidity identifier into a snake case Rust identifier, dropping leading underscores.
+fn accessor_name(label: &str) -> String {
+    let mut name = String::new();
+    let mut prev_lower = false;
+    for c in label.trim_start_matches('_').chars() {
+        if c.is_ascii_uppercase() {
+            if prev_lower {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+            prev_lower = false;
+        } else {
+            name.push(c);
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUT: &str = r#"{
+        "storage": [
+            {"astId": 3, "contract": "Token.sol:Token", "label": "_balances", "offset": 0, "slot": "0", "type": "t_mapping(t_address,t_uint256)"},
+            {"astId": 9, "contract": "Token.sol:Token", "label": "_allowances", "offset": 0, "slot": "1", "type": "t_mapping(t_address,t_mapping(t_address,t_uint256))"},
+            {"astId": 11, "contract": "Token.sol:Token", "label": "totalSupply", "offset": 0, "slot": "2", "type": "t_uint256"},
+            {"astId": 13, "contract": "Token.sol:Token", "label": "owner", "offset": 0, "slot": "3", "type": "t_address"},
+            {"astId": 15, "contract": "Token.sol:Token", "label": "paused", "offset": 20, "slot": "3", "type": "t_bool"},
+            {"astId": 17, "contract": "Token.sol:Token", "label": "name", "offset": 0, "slot": "4", "type": "t_string_storage"}
+        ],
+        "types": {
+            "t_address": {"encoding": "inplace", "label": "address", "numberOfBytes": "20"},
+            "t_bool": {"encoding": "inplace", "label": "bool", "numberOfBytes": "1"},
+            "t_mapping(t_address,t_mapping(t_address,t_uint256))": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => mapping(address => uint256))", "numberOfBytes": "32", "value": "t_mapping(t_address,t_uint256)"},
+            "t_mapping(t_address,t_uint256)": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => uint256)", "numberOfBytes": "32", "value": "t_uint256"},
+            "t_string_storage": {"encoding": "bytes", "label": "string", "numberOfBytes": "32"},
+            "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"}
+        }
+    }"#;
+
+    #[test]
+    fn test_accessor_name() {
+        assert_eq!(accessor_name("_balances"), "balances");
+        assert_eq!(accessor_name("totalSupply"), "total_supply");
+        assert_eq!(accessor_name("ERC20Name"), "erc20_name");
+    }
+
+    #[test]
+    fn test_expand() {
+        let layout: StorageLayout = serde_json::from_str(LAYOUT).unwrap();
+        let item: ItemStruct = syn::parse_quote! { pub struct Token; };
+        let expanded = expand(&item, &layout).unwrap().to_string();
+
+        for accessor in [
+            "fn balances",
+            "fn allowances",
+            "fn total_supply",
+            "fn owner",
+            "fn paused",
+            "fn name_slot",
+        ] {
+            assert!(expanded.contains(accessor), "missing `{accessor}`");
+        }
+        assert!(expanded.contains("key1"));
+    }
+
+    #[test]
+    fn test_expand_typed_keys_and_keywords() {
+        let layout: StorageLayout = serde_json::from_str(
+            r#"{
+                "storage": [
+                    {"astId": 3, "contract": "ERC165.sol:ERC165", "label": "_supportedInterfaces", "offset": 0, "slot": "0", "type": "t_mapping(t_bytes4,t_bool)"},
+                    {"astId": 5, "contract": "ERC165.sol:ERC165", "label": "deltas", "offset": 0, "slot": "1", "type": "t_mapping(t_int64,t_int24)"},
+                    {"astId": 7, "contract": "ERC165.sol:ERC165", "label": "type", "offset": 0, "slot": "2", "type": "t_bytes4"}
+                ],
+                "types": {
+                    "t_bool": {"encoding": "inplace", "label": "bool", "numberOfBytes": "1"},
+                    "t_bytes4": {"encoding": "inplace", "label": "bytes4", "numberOfBytes": "4"},
+                    "t_int24": {"encoding": "inplace", "label": "int24", "numberOfBytes": "3"},
+                    "t_int64": {"encoding": "inplace", "label": "int64", "numberOfBytes": "8"},
+                    "t_mapping(t_bytes4,t_bool)": {"encoding": "mapping", "key": "t_bytes4", "label": "mapping(bytes4 => bool)", "numberOfBytes": "32", "value": "t_bool"},
+                    "t_mapping(t_int64,t_int24)": {"encoding": "mapping", "key": "t_int64", "label": "mapping(int64 => int24)", "numberOfBytes": "32", "value": "t_int24"}
+                }
+            }"#,
+        )
+        .unwrap();
+        let item: ItemStruct = syn::parse_quote! { pub struct Registry; };
+        let expanded = expand(&item, &layout).unwrap().to_string();
+
+        assert!(expanded.contains("fn supported_interfaces"));
+        assert!(expanded.contains("key0 : :: alloy_primitives :: FixedBytes < 4 >"));
+        assert!(expanded.contains("key0 : i64"));
+        assert!(expanded.contains("Result < i32"));
+        assert!(expanded.contains("fn r#type"));
+    }
+
+    #[test]
+    fn test_accessor_ident() {
+        assert_eq!(accessor_ident("owner").unwrap().to_string(), "owner");
+        assert_eq!(accessor_ident("match").unwrap().to_string(), "r#match");
+        assert!(accessor_ident("self").is_err());
+        assert!(accessor_ident("").is_err());
+        assert!(accessor_ident("$owner").is_err());
+    }
+
+    #[test]
+    fn test_expand_empty_name() {
+        let layout: StorageLayout = serde_json::from_str(
+            r#"{
+                "storage": [
+                    {"astId": 3, "contract": "A.sol:A", "label": "__", "offset": 0, "slot": "0", "type": "t_uint256"}
+                ],
+                "types": {
+                    "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"}
+                }
+            }"#,
+        )
+        .unwrap();
+        let item: ItemStruct = syn::parse_quote! { pub struct A; };
+        assert!(expand(&item, &layout).is_err());
+    }
+}
@@ -63,6 +63,9 @@ pub mod mmr;
 /// for efficient state and proof management.
 pub mod mpt;
 
+/// Computes storage slots and decodes packed values following the Solidity storage layout.
+pub mod storage_layout;
+
 // Conditional compilation based on the operating system target.
 cfg_if! {
     if #[cfg(target_os = "zkvm")] {
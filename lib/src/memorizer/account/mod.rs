@@ -1,4 +1,7 @@
-use super::{keys::AccountKey, MemorizerError};
+use super::{
+    keys::{AccountCreationKey, AccountKey},
+    MemorizerError,
+};
 use alloy_consensus::Account;
 use cfg_if::cfg_if;
 
@@ -15,9 +18,19 @@ use cfg_if::cfg_if;
 /// - The header is checked and verified first. If it exists but has an `is_verified` flag of `false`, it is verified.
 /// - After verifying the header, the account is checked, and if the `is_verified` flag is `false`, it undergoes verification.
 /// - If either the header or account has an `is_verified` flag of `true`, it is read directly from the memorizer without re-verification.
+///
+/// ### Creation Block
+/// In online mode, [`AccountMemorizer::get_account_creation`] binary-searches with `eth_getProof`
+/// for the block `N` at which the account exists while it did not at `N - 1`.
+/// In zkVM mode, the existence of the account at `N` and its absence from the state trie at `N - 1`
+/// are verified. An account that was destroyed and created again may have several such blocks,
+/// and any of them may be found.
 pub trait AccountMemorizer {
     /// Retrieves account data based on the provided [`AccountKey`].
     fn get_account(&mut self, key: AccountKey) -> Result<Account, MemorizerError>;
+
+    /// Retrieves the block at which the account of the provided [`AccountCreationKey`] was created.
+    fn get_account_creation(&mut self, key: AccountCreationKey) -> Result<u64, MemorizerError>;
 }
 
 cfg_if! {
@@ -1,13 +1,19 @@
 use super::AccountMemorizer;
 use crate::account::AccountProvider;
-use crate::memorizer::values::{AccountMemorizerValue, MemorizerValue};
-use crate::memorizer::{keys::AccountKey, Memorizer};
-use crate::memorizer::{HeaderKey, HeaderMemorizer, MemorizerError};
+use crate::memorizer::values::{
+    AccountCreationMemorizerValue, AccountMemorizerValue, MemorizerValue,
+};
+use crate::memorizer::{
+    keys::{AccountCreationKey, AccountKey},
+    Memorizer,
+};
+use crate::memorizer::{HeaderKey, HeaderMemorizer, MemorizerError, MemorizerKey};
+use crate::mmr::MmrHasher;
 use alloy_consensus::Account;
-use alloy_primitives::Bytes;
+use alloy_primitives::{Bytes, B256, KECCAK256_EMPTY};
 use tokio::runtime::Runtime;
 
-impl AccountMemorizer for Memorizer {
+impl<H: MmrHasher> AccountMemorizer for Memorizer<H> {
     fn get_account(&mut self, key: AccountKey) -> Result<Account, MemorizerError> {
         let header_key = HeaderKey {
             block_number: key.block_number,
@@ -39,4 +45,79 @@ impl AccountMemorizer for Memorizer {
 
         Ok(account)
     }
+
+    fn get_account_creation(&mut self, key: AccountCreationKey) -> Result<u64, MemorizerError> {
+        let creation_key: MemorizerKey = key.clone().into();
+        if let Some((MemorizerValue::AccountCreation(creation_value), _)) =
+            self.map.get(&creation_key)
+        {
+            return Ok(creation_value.block_number);
+        }
+
+        let rt = Runtime::new()?;
+        let rpc_url = self
+            .chain_map
+            .get(&key.chain_id)
+            .ok_or(MemorizerError::MissingRpcUrl(key.chain_id))?
+            .to_owned();
+        let (block_number, exclusion_proof) = rt.block_on(async {
+            let client: AccountProvider = AccountProvider::new(rpc_url);
+            let (account, _) = client.get_account(key.address, key.block_number).await?;
+            if !account_exists(&account) {
+                return Err(MemorizerError::MissingAccount);
+            }
+            let (account, _) = client.get_account(key.address, 0).await?;
+            if account_exists(&account) {
+                return Ok((0, Vec::new()));
+            }
+
+            // Invariant: the account is absent at `low` and exists at `high`
+            let (mut low, mut high) = (0, key.block_number);
+            while high - low > 1 {
+                let mid = low + (high - low) / 2;
+                let (account, _) = client.get_account(key.address, mid).await?;
+                if account_exists(&account) {
+                    high = mid;
+                } else {
+                    low = mid;
+                }
+            }
+            let (_, exclusion_proof) = client.get_account(key.address, low).await?;
+            Ok((high, exclusion_proof))
+        })?;
+
+        let _ = self.get_account(AccountKey {
+            chain_id: key.chain_id,
+            block_number,
+            address: key.address,
+        })?;
+        if block_number > 0 {
+            let _ = self.get_header(HeaderKey {
+                chain_id: key.chain_id,
+                block_number: block_number - 1,
+            })?;
+        }
+
+        self.map.insert(
+            creation_key,
+            (
+                MemorizerValue::AccountCreation(AccountCreationMemorizerValue {
+                    block_number,
+                    exclusion_proof,
+                }),
+                false,
+            ),
+        );
+
+        Ok(block_number)
+    }
+}
+
+/// Returns `true` if the account returned by `eth_getProof` is present in the state trie.
+///
+/// Nodes report absent accounts as empty, and no empty account can be created since EIP-161.
+fn account_exists(account: &Account) -> bool {
+    account.nonce != 0
+        || !account.balance.is_zero()
+        || (account.code_hash != KECCAK256_EMPTY && account.code_hash != B256::ZERO)
 }
@@ -1,13 +1,14 @@
 use super::AccountMemorizer;
 use crate::memorizer::{
-    keys::{AccountKey, HeaderKey, MemorizerKey},
+    keys::{AccountCreationKey, AccountKey, HeaderKey, MemorizerKey},
     values::MemorizerValue,
     HeaderMemorizer, Memorizer, MemorizerError,
 };
+use crate::mmr::MmrHasher;
 use crate::mpt::Mpt;
 use alloy_consensus::Account;
 
-impl AccountMemorizer for Memorizer {
+impl<H: MmrHasher> AccountMemorizer for Memorizer<H> {
     fn get_account(&mut self, key: AccountKey) -> Result<Account, MemorizerError> {
         // 1. Header
         let header_key = HeaderKey {
@@ -42,4 +43,48 @@ impl AccountMemorizer for Memorizer {
             Err(MemorizerError::MissingAccount)
         }
     }
+
+    fn get_account_creation(&mut self, key: AccountCreationKey) -> Result<u64, MemorizerError> {
+        let creation_key: MemorizerKey = key.clone().into();
+        let (block_number, exclusion_proof) = match self.map.get(&creation_key) {
+            Some((MemorizerValue::AccountCreation(creation_value), true)) => {
+                println!("Account creation already verified");
+                return Ok(creation_value.block_number);
+            }
+            Some((MemorizerValue::AccountCreation(creation_value), false)) => (
+                creation_value.block_number,
+                creation_value.exclusion_proof.clone(),
+            ),
+            _ => return Err(MemorizerError::MissingAccount),
+        };
+        if block_number > key.block_number {
+            return Err(MemorizerError::InvalidAccountCreation);
+        }
+
+        // The account must exist at the creation block ...
+        let _ = self.get_account(AccountKey {
+            chain_id: key.chain_id,
+            block_number,
+            address: key.address,
+        })?;
+
+        // ... and be absent at the previous block.
+        if block_number > 0 {
+            let header = self.get_header(HeaderKey {
+                chain_id: key.chain_id,
+                block_number: block_number - 1,
+            })?;
+            let mpt = Mpt {
+                root: header.state_root,
+            };
+            println!("cycle-tracker-start: mpt(account exclusion)");
+            mpt.verify_account_exclusion(exclusion_proof, key.address)?;
+            println!("cycle-tracker-end: mpt(account exclusion)");
+        }
+
+        if let Some((_, is_verified)) = self.map.get_mut(&creation_key) {
+            *is_verified = true;
+        }
+        Ok(block_number)
+    }
 }
@@ -8,12 +8,13 @@ use crate::memorizer::HeaderKey;
 use crate::memorizer::HeaderMemorizer;
 use crate::memorizer::MemorizerError;
 use crate::memorizer::{keys::BeaconHeaderKey, Memorizer};
+use crate::mmr::MmrHasher;
 use tokio::runtime::Runtime;
 
 const SEPOLIA_POS_TRANSITION_BLOCK_NUMBER: u64 = 1450409;
 const MAINNET_POS_TRANSITION_BLOCK_NUMBER: u64 = 15537393;
 
-impl ClHeaderMemorizer for Memorizer {
+impl<H: MmrHasher> ClHeaderMemorizer for Memorizer<H> {
     fn get_cl_header(&mut self, key: BeaconHeaderKey) -> Result<BeaconHeader, MemorizerError> {
         // Header
         let header_key = HeaderKey {
@@ -4,9 +4,10 @@ use crate::memorizer::{
     values::MemorizerValue,
     HeaderMemorizer, Memorizer, MemorizerError,
 };
+use crate::mmr::MmrHasher;
 use ssz_rs::HashTreeRoot;
 
-impl ClHeaderMemorizer for Memorizer {
+impl<H: MmrHasher> ClHeaderMemorizer for Memorizer<H> {
     fn get_cl_header(&mut self, key: BeaconHeaderKey) -> Result<BeaconHeader, MemorizerError> {
         // 1. Header
         let header_key = HeaderKey {
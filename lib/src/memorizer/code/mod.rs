@@ -0,0 +1,29 @@
+use super::{keys::CodeKey, MemorizerError};
+use alloy_primitives::Bytes;
+use cfg_if::cfg_if;
+
+/// Defines a trait for managing and retrieving account bytecode from the memorizer.
+///
+/// ### Online Mode
+/// In online mode:
+/// - The account at the requested block is fetched first, together with its dependent header.
+/// - Once the account is present, an `eth_getCode` request retrieves the bytecode at that block.
+/// - The bytecode is then saved to the memorizer for future access.
+///
+/// ### zkVM Mode
+/// In zkVM (Zero-Knowledge Virtual Machine) mode:
+/// - The header and the account are checked and verified first, as in [`super::AccountMemorizer`].
+/// - The bytecode is then checked against the verified account: `keccak256(code)` must equal the account's `code_hash`.
+/// - If the bytecode has an `is_verified` flag of `true`, it is read directly from the memorizer without re-verification.
+pub trait CodeMemorizer {
+    /// Retrieves account bytecode based on the provided [`CodeKey`].
+    fn get_code(&mut self, key: CodeKey) -> Result<Bytes, MemorizerError>;
+}
+
+cfg_if! {
+    if #[cfg(target_os = "zkvm")] {
+        mod zkvm;
+    } else {
+        mod online;
+    }
+}
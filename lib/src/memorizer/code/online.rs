@@ -0,0 +1,43 @@
+use super::CodeMemorizer;
+use crate::account::AccountProvider;
+use crate::memorizer::values::{CodeMemorizerValue, MemorizerValue};
+use crate::memorizer::{keys::CodeKey, Memorizer};
+use crate::memorizer::{AccountKey, AccountMemorizer, MemorizerError};
+use crate::mmr::MmrHasher;
+use alloy_primitives::Bytes;
+use tokio::runtime::Runtime;
+
+impl<H: MmrHasher> CodeMemorizer for Memorizer<H> {
+    fn get_code(&mut self, key: CodeKey) -> Result<Bytes, MemorizerError> {
+        let account_key = AccountKey {
+            block_number: key.block_number,
+            chain_id: key.chain_id,
+            address: key.address,
+        };
+        let _ = self.get_account(account_key)?;
+
+        let rt = Runtime::new()?;
+        let rpc_url = self
+            .chain_map
+            .get(&key.chain_id)
+            .ok_or(MemorizerError::MissingRpcUrl(key.chain_id))?
+            .to_owned();
+        let code: Bytes = rt.block_on(async {
+            let client: AccountProvider = AccountProvider::new(rpc_url);
+            client
+                .get_code(key.address, key.block_number)
+                .await
+                .map_err(MemorizerError::TransportError)
+        })?;
+
+        self.map.insert(
+            key.into(),
+            (
+                MemorizerValue::Code(CodeMemorizerValue { code: code.clone() }),
+                false,
+            ),
+        );
+
+        Ok(code)
+    }
+}
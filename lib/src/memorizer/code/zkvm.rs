@@ -0,0 +1,46 @@
+use super::CodeMemorizer;
+use crate::memorizer::{
+    keys::{AccountKey, CodeKey, MemorizerKey},
+    values::MemorizerValue,
+    AccountMemorizer, Memorizer, MemorizerError,
+};
+use crate::mmr::MmrHasher;
+use alloy_primitives::{keccak256, Bytes};
+
+impl<H: MmrHasher> CodeMemorizer for Memorizer<H> {
+    fn get_code(&mut self, key: CodeKey) -> Result<Bytes, MemorizerError> {
+        // 1. Header & Account
+        let account_key = AccountKey {
+            block_number: key.block_number,
+            chain_id: key.chain_id,
+            address: key.address,
+        };
+        let account = self.get_account(account_key)?;
+
+        // 2. Code
+        let code_key: MemorizerKey = key.into();
+
+        if let Some((MemorizerValue::Code(code_value), is_verified)) = self.map.get_mut(&code_key) {
+            if *is_verified {
+                println!("Code already verified");
+                Ok(code_value.code.clone())
+            } else {
+                println!("cycle-tracker-start: code hash");
+                let code_hash = keccak256(&code_value.code);
+                println!("cycle-tracker-end: code hash");
+                if code_hash == account.code_hash {
+                    *is_verified = true;
+                    Ok(code_value.code.clone())
+                } else {
+                    println!(
+                        "Mismatched code hash: account code hash: {:?}, code hash: {:?}",
+                        account.code_hash, code_hash
+                    );
+                    Err(MemorizerError::InvalidCode)
+                }
+            }
+        } else {
+            Err(MemorizerError::MissingCode)
+        }
+    }
+}
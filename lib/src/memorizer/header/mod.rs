@@ -1,5 +1,11 @@
-use super::{keys::HeaderKey, MemorizerError};
+use super::{
+    keys::{ChainedHeaderKey, HeaderKey, HeadersKey, TimestampKey},
+    values::HeaderMemorizerValue,
+    MemorizerError,
+};
 use alloy_consensus::Header;
+use alloy_primitives::{hex, keccak256};
+use alloy_rlp::Decodable;
 use cfg_if::cfg_if;
 
 /// Defines a trait for managing and retrieving block headers from the memorizer.
@@ -10,11 +16,46 @@ use cfg_if::cfg_if;
 ///
 /// ### zkVM Mode
 /// In zkVM (Zero-Knowledge Virtual Machine) mode:
-/// - The header is retrieved from the memorizer if present. If it exists but its `is_verified` flag is `false`, it undergoes verification.
+/// - The header is retrieved from the memorizer if present. If it exists but its `is_verified` flag is `false`, it undergoes verification:
+///   the MMR leaf of the header, hashed with the hash function of the MMR, must be its element
+///   hash, which must be proven against the MMR.
 /// - If the `is_verified` flag is `true`, the header is read directly from the memorizer without additional verification.
+///
+/// ### Batch Retrieval
+/// In online mode, [`HeaderMemorizer::get_headers`] fetches every missing header from the Indexer
+/// in chunked requests, all under a single MMR snapshot, and memorizes them in bulk together with
+/// an MMR multi-proof covering them. If headers memorized earlier were proven against another
+/// snapshot, every requested header is fetched again under the current one. Headers reached by
+/// [`HeaderMemorizer::get_chained_header`] have no MMR proof and cannot be requested in a batch.
+/// In zkVM mode, the headers are verified at once against the multi-proof, sharing the sibling
+/// hashes of neighbouring headers.
+///
+/// ### Lookup by Timestamp
+/// In online mode, the block `N` with `timestamp(N) <= timestamp < timestamp(N + 1)` is found by
+/// binary search over the RPC, and the headers of `N` and `N + 1` are memorized.
+/// In zkVM mode, both headers are verified, `N + 1` must link to `N` by its parent hash, and the
+/// timestamp must fall between them, which proves that `N` is the right block.
+///
+/// ### Chaining Recent Headers
+/// Blocks not yet in the MMR are reached from an anchor block in the MMR through
+/// [`HeaderMemorizer::get_chained_header`].
+/// In online mode, the anchor header is fetched from the Indexer and the headers between both
+/// blocks are fetched over the RPC with `debug_getRawHeader`, which the RPC provider must serve.
+/// In zkVM mode, the anchor header is verified against the MMR and every header must link to the
+/// previous one by its parent hash. The requested header is then served as verified by
+/// [`HeaderMemorizer::get_header`], so that state can be read from it.
 pub trait HeaderMemorizer {
     /// Retrieves a block header based on the provided [`HeaderKey`].
     fn get_header(&mut self, key: HeaderKey) -> Result<Header, MemorizerError>;
+
+    /// Retrieves many block headers based on the provided [`HeadersKey`], in the order of its block numbers.
+    fn get_headers(&mut self, key: HeadersKey) -> Result<Vec<Header>, MemorizerError>;
+
+    /// Retrieves the header of the last block produced at or before the timestamp of the provided [`TimestampKey`].
+    fn get_header_at_timestamp(&mut self, key: TimestampKey) -> Result<Header, MemorizerError>;
+
+    /// Retrieves a block header linked by parent hashes to the anchor block of the provided [`ChainedHeaderKey`].
+    fn get_chained_header(&mut self, key: ChainedHeaderKey) -> Result<Header, MemorizerError>;
 }
 
 cfg_if! {
@@ -24,3 +65,18 @@ cfg_if! {
         mod online;
     }
 }
+
+/// Builds the memorized value of a header reached by parent hashes rather than by an MMR proof.
+///
+/// Its element hash is the keccak block hash, which the parent hashes of the chain link to, whatever
+/// the hash function of the MMR. Its element index of 0 is invalid in any MMR, so that only a
+/// verified header chain serves it in zkVM mode.
+fn chained_header_value(rlp: &[u8]) -> Result<HeaderMemorizerValue, MemorizerError> {
+    Ok(HeaderMemorizerValue {
+        header: Header::decode(&mut &rlp[..])?,
+        element_index: 0,
+        element_hash: keccak256(rlp),
+        rlp: hex::encode(rlp),
+        proof: Vec::new(),
+    })
+}
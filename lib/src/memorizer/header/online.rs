@@ -1,14 +1,23 @@
-use super::HeaderMemorizer;
+use super::{chained_header_value, HeaderMemorizer};
 use crate::memorizer::{
-    keys::HeaderKey,
-    values::{HeaderMemorizerValue, MemorizerValue},
+    keys::{ChainedHeaderKey, HeaderKey, HeadersKey, TimestampKey},
+    values::{
+        ChainedHeaderMemorizerValue, HeaderMemorizerValue, HeadersMemorizerValue, MemorizerValue,
+        TimestampMemorizerValue,
+    },
     Memorizer, MemorizerError, MemorizerKey,
 };
-use crate::{header::IndexerRpc, mmr::MmrMeta, provider::header::IndexerClient};
+use crate::{
+    block::BlockProvider,
+    chain::ChainId,
+    header::{HeaderProofs, HeaderRpc},
+    mmr::{MmrHasher, MmrMultiProof},
+    provider::header::IndexerClient,
+};
 use alloy_consensus::Header;
 use tokio::runtime::Runtime;
 
-impl HeaderMemorizer for Memorizer {
+impl<H: MmrHasher> HeaderMemorizer for Memorizer<H> {
     fn get_header(&mut self, key: HeaderKey) -> Result<Header, MemorizerError> {
         let target_block_number = key.block_number;
         let target_chain_id = key.chain_id;
@@ -20,33 +29,293 @@ impl HeaderMemorizer for Memorizer {
         } else {
             // If not, fetch from indexer
             let rt = Runtime::new()?;
-            let block: IndexerRpc = rt.block_on(async {
-                let client = IndexerClient::new(target_chain_id, self.to_chain_id);
+            let block: HeaderProofs<H> = rt.block_on(async {
+                let client =
+                    IndexerClient::new(target_chain_id, self.to_chain_id).with_hasher::<H>();
                 client
                     .get_header(target_block_number)
                     .await
                     .map_err(MemorizerError::ReqwestError)
             })?;
-            let mmr: MmrMeta = block.meta.into();
-            let header: Header = block.proofs[0].rlp_block_header.clone().into();
+            let mmr = block.meta;
+            let value = header_value(block.proofs[0].clone());
+            let header = value.header.clone();
 
-            self.map.insert(
-                header_key,
-                (
-                    MemorizerValue::Header(HeaderMemorizerValue {
-                        header: header.clone(),
-                        element_index: block.proofs[0].element_index,
-                        element_hash: block.proofs[0].element_hash,
-                        rlp: block.proofs[0].rlp_block_header.string.clone(),
-                        proof: block.proofs[0].siblings_hashes.clone(),
-                    }),
-                    false,
-                ),
-            );
+            self.map
+                .insert(header_key, (MemorizerValue::Header(value), false));
             println!("mmr: {:#?}", mmr);
             self.mmr_meta.insert(target_chain_id, mmr);
 
             Ok(header)
         }
     }
+
+    fn get_headers(&mut self, key: HeadersKey) -> Result<Vec<Header>, MemorizerError> {
+        let chain_id = key.chain_id;
+        let mut missing: Vec<u64> = key
+            .block_numbers
+            .iter()
+            .copied()
+            .filter(|block_number| {
+                let header_key: MemorizerKey = HeaderKey {
+                    chain_id,
+                    block_number: *block_number,
+                }
+                .into();
+                !self.map.contains_key(&header_key)
+            })
+            .collect();
+        missing.sort_unstable();
+        missing.dedup();
+
+        if !missing.is_empty() {
+            self.memorize_headers(chain_id, &missing)?;
+        }
+
+        // Headers reached by a header chain have no MMR proof to share in the multi-proof.
+        if let Some(block_number) = key.block_numbers.iter().find(|block_number| {
+            let header_key: MemorizerKey = HeaderKey {
+                chain_id,
+                block_number: **block_number,
+            }
+            .into();
+            matches!(
+                self.map.get(&header_key),
+                Some((MemorizerValue::Header(header_value), _)) if header_value.element_index == 0
+            )
+        }) {
+            return Err(MemorizerError::ChainedHeaderInBatch(*block_number));
+        }
+
+        // Headers memorized earlier may carry proofs against an older snapshot: fetch every
+        // requested header again, so that all of them are proven against the same snapshot.
+        let mmr = self
+            .mmr_meta
+            .get_mut(&chain_id)
+            .ok_or(MemorizerError::MissingHeader)?;
+        mmr.validate_peaks()?;
+        let stale = key.block_numbers.iter().any(|block_number| {
+            let header_key: MemorizerKey = HeaderKey {
+                chain_id,
+                block_number: *block_number,
+            }
+            .into();
+            match self.map.get(&header_key) {
+                Some((MemorizerValue::Header(header_value), _)) => mmr
+                    .verify_proof(
+                        header_value.element_index,
+                        header_value.element_hash,
+                        header_value.proof.clone(),
+                    )
+                    .is_err(),
+                _ => true,
+            }
+        });
+        if stale {
+            let mut block_numbers = key.block_numbers.clone();
+            block_numbers.sort_unstable();
+            block_numbers.dedup();
+            self.memorize_headers(chain_id, &block_numbers)?;
+        }
+
+        let headers: Vec<HeaderMemorizerValue> = key
+            .block_numbers
+            .iter()
+            .map(|block_number| {
+                let header_key: MemorizerKey = HeaderKey {
+                    chain_id,
+                    block_number: *block_number,
+                }
+                .into();
+                match self.map.get(&header_key) {
+                    Some((MemorizerValue::Header(header_value), _)) => Ok(header_value.clone()),
+                    _ => Err(MemorizerError::MissingHeader),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mmr = self
+            .mmr_meta
+            .get(&chain_id)
+            .ok_or(MemorizerError::MissingHeader)?;
+        let multi_proof = MmrMultiProof::new(
+            mmr,
+            headers
+                .iter()
+                .map(|header_value| (header_value.element_index, header_value.proof.clone()))
+                .collect(),
+        )?;
+        self.map.insert(
+            key.into(),
+            (
+                MemorizerValue::Headers(HeadersMemorizerValue { multi_proof }),
+                false,
+            ),
+        );
+
+        Ok(headers
+            .into_iter()
+            .map(|header_value| header_value.header)
+            .collect())
+    }
+
+    fn get_header_at_timestamp(&mut self, key: TimestampKey) -> Result<Header, MemorizerError> {
+        let chain_id = key.chain_id;
+        let timestamp = key.timestamp;
+        let timestamp_key: MemorizerKey = key.into();
+
+        let block_number = match self.map.get(&timestamp_key) {
+            Some((MemorizerValue::Timestamp(timestamp_value), _)) => timestamp_value.block_number,
+            _ => {
+                let rt = Runtime::new()?;
+                let rpc_url = self
+                    .chain_map
+                    .get(&chain_id)
+                    .ok_or(MemorizerError::MissingRpcUrl(chain_id))?
+                    .to_owned();
+                let block_number = rt
+                    .block_on(async {
+                        let provider = BlockProvider::new(rpc_url);
+                        provider
+                            .find_block_by_timestamp(timestamp)
+                            .await
+                            .map_err(MemorizerError::TransportError)
+                    })?
+                    .ok_or(MemorizerError::InvalidTimestamp)?;
+
+                self.map.insert(
+                    timestamp_key,
+                    (
+                        MemorizerValue::Timestamp(TimestampMemorizerValue { block_number }),
+                        false,
+                    ),
+                );
+                block_number
+            }
+        };
+
+        // Both headers are needed in the zkVM to prove the timestamp lies between them.
+        let header = self.get_header(HeaderKey {
+            chain_id,
+            block_number,
+        })?;
+        let _ = self.get_header(HeaderKey {
+            chain_id,
+            block_number: block_number + 1,
+        })?;
+
+        Ok(header)
+    }
+
+    fn get_chained_header(&mut self, key: ChainedHeaderKey) -> Result<Header, MemorizerError> {
+        let chain_id = key.chain_id;
+        let block_number = key.block_number;
+        let anchor_block_number = key.anchor_block_number;
+        let header_key: MemorizerKey = HeaderKey {
+            chain_id,
+            block_number,
+        }
+        .into();
+        let chained_header_key: MemorizerKey = key.into();
+
+        // A header already memorized, with an MMR proof or a chain, needs no other chain.
+        if let Some((MemorizerValue::Header(header_value), _)) = self.map.get(&header_key) {
+            return Ok(header_value.header.clone());
+        }
+
+        let anchor_header = self.get_header(HeaderKey {
+            chain_id,
+            block_number: anchor_block_number,
+        })?;
+        if block_number == anchor_block_number {
+            return Ok(anchor_header);
+        }
+
+        // Every header from the requested block to the anchor, without the anchor
+        let block_numbers = if block_number > anchor_block_number {
+            anchor_block_number + 1..=block_number
+        } else {
+            block_number..=anchor_block_number - 1
+        };
+        let rt = Runtime::new()?;
+        let rpc_url = self
+            .chain_map
+            .get(&chain_id)
+            .ok_or(MemorizerError::MissingRpcUrl(chain_id))?
+            .to_owned();
+        let rlp_headers = rt.block_on(async {
+            let provider = BlockProvider::new(rpc_url);
+            provider
+                .get_raw_headers(block_numbers)
+                .await
+                .map_err(MemorizerError::TransportError)
+        })?;
+
+        let rlp = if block_number > anchor_block_number {
+            rlp_headers.last()
+        } else {
+            rlp_headers.first()
+        }
+        .ok_or(MemorizerError::MissingHeader)?;
+        let value = chained_header_value(rlp)?;
+        let header = value.header.clone();
+
+        self.map
+            .insert(header_key, (MemorizerValue::Header(value), false));
+        self.map.insert(
+            chained_header_key,
+            (
+                MemorizerValue::ChainedHeader(ChainedHeaderMemorizerValue { rlp_headers }),
+                false,
+            ),
+        );
+
+        Ok(header)
+    }
+}
+
+impl<H: MmrHasher> Memorizer<H> {
+    /// Fetches the headers of `block_numbers` from the Indexer under a single MMR snapshot, and
+    /// memorizes them together with that snapshot.
+    fn memorize_headers(
+        &mut self,
+        chain_id: ChainId,
+        block_numbers: &[u64],
+    ) -> Result<(), MemorizerError> {
+        let rt = Runtime::new()?;
+        let blocks: HeaderProofs<H> = rt.block_on(async {
+            let client = IndexerClient::new(chain_id, self.to_chain_id).with_hasher::<H>();
+            client
+                .get_headers(block_numbers)
+                .await
+                .map_err(MemorizerError::IndexerError)
+        })?;
+
+        for proof in blocks.proofs {
+            let header_key: MemorizerKey = HeaderKey {
+                chain_id,
+                block_number: proof.block_number as u64,
+            }
+            .into();
+            self.map.insert(
+                header_key,
+                (MemorizerValue::Header(header_value(proof)), false),
+            );
+        }
+        let mmr = blocks.meta;
+        println!("mmr: {:#?}", mmr);
+        self.mmr_meta.insert(chain_id, mmr);
+        Ok(())
+    }
+}
+
+/// Converts a header proof from the Indexer into a memorized header.
+fn header_value(proof: HeaderRpc) -> HeaderMemorizerValue {
+    HeaderMemorizerValue {
+        header: proof.rlp_block_header.clone().into(),
+        element_index: proof.element_index,
+        element_hash: proof.element_hash,
+        rlp: proof.rlp_block_header.string,
+        proof: proof.siblings_hashes,
+    }
 }
@@ -1,12 +1,14 @@
-use super::HeaderMemorizer;
+use super::{chained_header_value, HeaderMemorizer};
 use crate::memorizer::{
-    keys::{HeaderKey, MemorizerKey},
-    values::MemorizerValue,
+    keys::{ChainedHeaderKey, HeaderKey, HeadersKey, MemorizerKey, TimestampKey},
+    values::{HeaderMemorizerValue, MemorizerValue},
     Memorizer, MemorizerError,
 };
+use crate::mmr::{MmrError, MmrHasher};
 use alloy_consensus::Header;
+use alloy_primitives::B256;
 
-impl HeaderMemorizer for Memorizer {
+impl<H: MmrHasher> HeaderMemorizer for Memorizer<H> {
     fn get_header(&mut self, key: HeaderKey) -> Result<Header, MemorizerError> {
         let target_chain_id = key.chain_id;
         let header_key: MemorizerKey = key.into();
@@ -18,7 +20,16 @@ impl HeaderMemorizer for Memorizer {
                 println!("Header MMR already verified");
                 Ok(header_value.header.clone())
             } else {
-                let mmr = &self.mmr_meta.get(&target_chain_id).unwrap();
+                // The MMR proves the element hash, which must be the leaf of the served header.
+                if !is_leaf_of::<H>(header_value) {
+                    return Err(MemorizerError::MissingHeader);
+                }
+                let mmr = self.mmr_meta.get_mut(&target_chain_id).unwrap();
+                if !mmr.peaks_validated() {
+                    println!("cycle-tracker-start: mmr_peaks");
+                    mmr.validate_peaks()?;
+                    println!("cycle-tracker-end: mmr_peaks");
+                }
                 println!("cycle-tracker-start: mmr");
                 mmr.verify_proof(
                     header_value.element_index,
@@ -33,4 +44,208 @@ impl HeaderMemorizer for Memorizer {
             Err(MemorizerError::MissingHeader)
         }
     }
+
+    fn get_headers(&mut self, key: HeadersKey) -> Result<Vec<Header>, MemorizerError> {
+        let chain_id = key.chain_id;
+        let headers_key: MemorizerKey = key.clone().into();
+        let multi_proof = match self.map.get(&headers_key) {
+            Some((MemorizerValue::Headers(headers_value), false)) => {
+                Some(headers_value.multi_proof.clone())
+            }
+            _ => None,
+        };
+
+        // Verify every header at once, then serve them as already verified.
+        if let Some(multi_proof) = multi_proof {
+            let header_keys: Vec<MemorizerKey> = key
+                .block_numbers
+                .iter()
+                .map(|block_number| {
+                    HeaderKey {
+                        chain_id,
+                        block_number: *block_number,
+                    }
+                    .into()
+                })
+                .collect();
+            let mut elements: Vec<(u128, B256)> = header_keys
+                .iter()
+                .map(|header_key| match self.map.get(header_key) {
+                    Some((MemorizerValue::Header(header_value), _))
+                        if is_leaf_of::<H>(header_value) =>
+                    {
+                        Ok((header_value.element_index, header_value.element_hash))
+                    }
+                    _ => Err(MemorizerError::MissingHeader),
+                })
+                .collect::<Result<_, _>>()?;
+            // Only identical duplicates are merged: two hashes at one index fail the comparison.
+            elements.sort_unstable();
+            elements.dedup();
+            if !elements
+                .iter()
+                .map(|(element_index, _)| *element_index)
+                .eq(multi_proof.element_indices.iter().copied())
+            {
+                return Err(MmrError::InvalidProof.into());
+            }
+            let element_hashes: Vec<B256> = elements
+                .into_iter()
+                .map(|(_, element_hash)| element_hash)
+                .collect();
+
+            let mmr = self.mmr_meta.get_mut(&chain_id).unwrap();
+            if !mmr.peaks_validated() {
+                println!("cycle-tracker-start: mmr_peaks");
+                mmr.validate_peaks()?;
+                println!("cycle-tracker-end: mmr_peaks");
+            }
+            println!("cycle-tracker-start: mmr_multi_proof");
+            mmr.verify_multi_proof(&element_hashes, &multi_proof)?;
+            println!("cycle-tracker-end: mmr_multi_proof");
+
+            for header_key in header_keys.iter().chain([&headers_key]) {
+                if let Some((_, is_verified)) = self.map.get_mut(header_key) {
+                    *is_verified = true;
+                }
+            }
+        }
+
+        key.block_numbers
+            .into_iter()
+            .map(|block_number| {
+                self.get_header(HeaderKey {
+                    chain_id,
+                    block_number,
+                })
+            })
+            .collect()
+    }
+
+    fn get_header_at_timestamp(&mut self, key: TimestampKey) -> Result<Header, MemorizerError> {
+        let chain_id = key.chain_id;
+        let timestamp = key.timestamp;
+        let timestamp_key: MemorizerKey = key.into();
+
+        let block_number = match self.map.get(&timestamp_key) {
+            Some((MemorizerValue::Timestamp(timestamp_value), true)) => {
+                println!("Timestamp already verified");
+                let block_number = timestamp_value.block_number;
+                return self.get_header(HeaderKey {
+                    chain_id,
+                    block_number,
+                });
+            }
+            Some((MemorizerValue::Timestamp(timestamp_value), false)) => {
+                timestamp_value.block_number
+            }
+            _ => return Err(MemorizerError::MissingHeader),
+        };
+
+        let header = self.get_header(HeaderKey {
+            chain_id,
+            block_number,
+        })?;
+        let next_header = self.get_header(HeaderKey {
+            chain_id,
+            block_number: block_number + 1,
+        })?;
+
+        // The headers must be adjacent and the timestamp must fall between them.
+        if next_header.parent_hash != header.hash_slow()
+            || header.timestamp > timestamp
+            || timestamp >= next_header.timestamp
+        {
+            return Err(MemorizerError::InvalidTimestamp);
+        }
+
+        if let Some((_, is_verified)) = self.map.get_mut(&timestamp_key) {
+            *is_verified = true;
+        }
+        Ok(header)
+    }
+
+    fn get_chained_header(&mut self, key: ChainedHeaderKey) -> Result<Header, MemorizerError> {
+        let chain_id = key.chain_id;
+        let block_number = key.block_number;
+        let anchor_block_number = key.anchor_block_number;
+        let header_key = HeaderKey {
+            chain_id,
+            block_number,
+        };
+        let chained_header_key: MemorizerKey = key.into();
+
+        let rlp_headers = match self.map.get(&chained_header_key) {
+            Some((MemorizerValue::ChainedHeader(value), false)) => value.rlp_headers.clone(),
+            // Either the chain is verified, or the header was memorized with an MMR proof.
+            _ => return self.get_header(header_key),
+        };
+
+        let anchor_header = self.get_header(HeaderKey {
+            chain_id,
+            block_number: anchor_block_number,
+        })?;
+
+        println!("cycle-tracker-start: header_chain");
+        let chain = rlp_headers
+            .iter()
+            .map(|rlp| chained_header_value(rlp))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Every header must be the child of the previous one, the anchor included.
+        let mut links: Vec<(u64, B256, B256)> = chain
+            .iter()
+            .map(|value| {
+                (
+                    value.header.number,
+                    value.header.parent_hash,
+                    value.element_hash,
+                )
+            })
+            .collect();
+        let anchor_position = if block_number > anchor_block_number {
+            0
+        } else {
+            links.len()
+        };
+        links.insert(
+            anchor_position,
+            (
+                anchor_header.number,
+                anchor_header.parent_hash,
+                anchor_header.hash_slow(),
+            ),
+        );
+        if !links.windows(2).all(|pair| {
+            let (parent_number, _, parent_hash) = pair[0];
+            let (number, header_parent_hash, _) = pair[1];
+            number == parent_number + 1 && header_parent_hash == parent_hash
+        }) {
+            return Err(MemorizerError::InvalidHeaderChain);
+        }
+
+        // The chain must end at the requested block on the other side of the anchor.
+        let target = if block_number > anchor_block_number {
+            chain.last()
+        } else {
+            chain.first()
+        }
+        .filter(|value| value.header.number == block_number)
+        .cloned()
+        .ok_or(MemorizerError::InvalidHeaderChain)?;
+        println!("cycle-tracker-end: header_chain");
+
+        let header = target.header.clone();
+        self.map
+            .insert(header_key.into(), (MemorizerValue::Header(target), true));
+        if let Some((_, is_verified)) = self.map.get_mut(&chained_header_key) {
+            *is_verified = true;
+        }
+        Ok(header)
+    }
+}
+
+/// Returns whether the element hash of `header_value` is the MMR leaf of its header, hashed with `H`.
+fn is_leaf_of<H: MmrHasher>(header_value: &HeaderMemorizerValue) -> bool {
+    H::hash_leaf(&alloy_rlp::encode(&header_value.header)) == header_value.element_hash
 }
@@ -1,5 +1,6 @@
 use alloy_primitives::{keccak256, Address, B256};
 use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
 
 use crate::chain::ChainId;
 
@@ -15,6 +16,51 @@ pub struct HeaderKey {
     pub block_number: u64,
 }
 
+/// Key for identifying many block headers within a chain.
+///
+/// Each header is memorized under its own [`HeaderKey`], while the MMR multi-proof
+/// covering them is memorized under the [`HeadersKey`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeadersKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Block numbers within the chain.
+    pub block_numbers: Vec<u64>,
+}
+
+impl HeadersKey {
+    /// Creates a new [`HeadersKey`] for every block of an inclusive range.
+    pub fn range(chain_id: ChainId, block_numbers: RangeInclusive<u64>) -> Self {
+        Self {
+            chain_id,
+            block_numbers: block_numbers.collect(),
+        }
+    }
+}
+
+/// Key for identifying the block header covering a specific timestamp.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimestampKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Unix timestamp, in seconds.
+    pub timestamp: u64,
+}
+
+/// Key for identifying a block header linked by parent hashes to the header of an anchor block.
+///
+/// The anchor block must be in the MMR, while the requested block may not be yet. The header
+/// is also memorized under its own [`HeaderKey`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChainedHeaderKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Block number within the chain.
+    pub block_number: u64,
+    /// Number of a block in the MMR, before or after the requested block.
+    pub anchor_block_number: u64,
+}
+
 /// Key for identifying a specific account within a block.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AccountKey {
@@ -26,6 +72,17 @@ pub struct AccountKey {
     pub address: Address,
 }
 
+/// Key for identifying the block at which an account was created.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountCreationKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Block number at which the account is known to exist, bounding the search.
+    pub block_number: u64,
+    /// Address of the account.
+    pub address: Address,
+}
+
 /// Key for identifying a specific storage entry within an account.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StorageKey {
@@ -39,6 +96,59 @@ pub struct StorageKey {
     pub storage_slot: B256,
 }
 
+/// Key for identifying several storage entries of one account within a block.
+///
+/// Each entry is memorized under its own [`StorageKey`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoragesKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Block number at which the storage data is relevant.
+    pub block_number: u64,
+    /// Address of the account holding the storage.
+    pub address: Address,
+    /// Storage slots within the account.
+    pub storage_slots: Vec<B256>,
+}
+
+impl StoragesKey {
+    /// Returns the [`StorageKey`] of every storage slot, in order.
+    pub fn storage_keys(&self) -> impl Iterator<Item = StorageKey> + '_ {
+        self.storage_slots.iter().map(|storage_slot| StorageKey {
+            chain_id: self.chain_id,
+            block_number: self.block_number,
+            address: self.address,
+            storage_slot: *storage_slot,
+        })
+    }
+}
+
+/// Key for identifying the change point of a storage slot within an inclusive range of blocks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageChangeKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// First block number of the range.
+    pub from_block: u64,
+    /// Last block number of the range, inclusive.
+    pub to_block: u64,
+    /// Address of the account holding the storage.
+    pub address: Address,
+    /// Specific storage slot within the account.
+    pub storage_slot: B256,
+}
+
+/// Key for identifying the bytecode of an account within a block.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CodeKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Block number at which the bytecode is relevant.
+    pub block_number: u64,
+    /// Address of the account holding the bytecode.
+    pub address: Address,
+}
+
 /// Key for identifying a specific transaction within a block.
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct TransactionKey {
@@ -50,6 +160,26 @@ pub struct TransactionKey {
     pub transaction_index: u64,
 }
 
+/// Key for identifying the recovered sender of a specific transaction within a block.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SenderKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Block number containing the transaction.
+    pub block_number: u64,
+    /// Index of the transaction within the block.
+    pub transaction_index: u64,
+}
+
+/// Key for identifying the number of transactions within a block.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionCountKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Block number whose transactions are counted.
+    pub block_number: u64,
+}
+
 /// Key for identifying a specific receipt within a block.
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ReceiptKey {
@@ -61,6 +191,35 @@ pub struct ReceiptKey {
     pub transaction_index: u64,
 }
 
+/// Key for identifying a specific withdrawal within a block.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WithdrawalKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Block number containing the withdrawal.
+    pub block_number: u64,
+    /// Index of the withdrawal within the block.
+    pub withdrawal_index: u64,
+}
+
+/// Key for identifying a transaction by its hash.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionHashKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Hash of the transaction.
+    pub transaction_hash: B256,
+}
+
+/// Key for identifying a receipt by the hash of its transaction.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReceiptHashKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Hash of the transaction the receipt belongs to.
+    pub transaction_hash: B256,
+}
+
 /// Key for identifying a specific consensus layer (beacon) header.
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct BeaconHeaderKey {
@@ -78,12 +237,44 @@ impl From<HeaderKey> for MemorizerKey {
     }
 }
 
+impl From<HeadersKey> for MemorizerKey {
+    fn from(value: HeadersKey) -> Self {
+        let mut data = bincode::serialize(&value).expect("bincode serde error");
+        data.extend("HeadersKey".as_bytes());
+        Self(*keccak256(data))
+    }
+}
+
+impl From<TimestampKey> for MemorizerKey {
+    fn from(value: TimestampKey) -> Self {
+        let mut data = bincode::serialize(&value).expect("bincode serde error");
+        data.extend("TimestampKey".as_bytes());
+        Self(*keccak256(data))
+    }
+}
+
+impl From<ChainedHeaderKey> for MemorizerKey {
+    fn from(value: ChainedHeaderKey) -> Self {
+        let mut data = bincode::serialize(&value).expect("bincode serde error");
+        data.extend("ChainedHeaderKey".as_bytes());
+        Self(*keccak256(data))
+    }
+}
+
 impl From<AccountKey> for MemorizerKey {
     fn from(value: AccountKey) -> Self {
         Self(*keccak256(bincode::serialize(&value).unwrap()))
     }
 }
 
+impl From<AccountCreationKey> for MemorizerKey {
+    fn from(value: AccountCreationKey) -> Self {
+        let mut data = bincode::serialize(&value).expect("bincode serde error");
+        data.extend("AccountCreationKey".as_bytes());
+        Self(*keccak256(data))
+    }
+}
+
 impl From<StorageKey> for MemorizerKey {
     fn from(value: StorageKey) -> Self {
         Self(*keccak256(
@@ -92,6 +283,22 @@ impl From<StorageKey> for MemorizerKey {
     }
 }
 
+impl From<StorageChangeKey> for MemorizerKey {
+    fn from(value: StorageChangeKey) -> Self {
+        let mut data = bincode::serialize(&value).expect("bincode serde error");
+        data.extend("StorageChangeKey".as_bytes());
+        Self(*keccak256(data))
+    }
+}
+
+impl From<CodeKey> for MemorizerKey {
+    fn from(value: CodeKey) -> Self {
+        let mut data = bincode::serialize(&value).expect("bincode serde error");
+        data.extend("CodeKey".as_bytes());
+        Self(*keccak256(data))
+    }
+}
+
 impl From<TransactionKey> for MemorizerKey {
     fn from(value: TransactionKey) -> Self {
         Self(*keccak256(
@@ -100,11 +307,53 @@ impl From<TransactionKey> for MemorizerKey {
     }
 }
 
+impl From<SenderKey> for MemorizerKey {
+    fn from(value: SenderKey) -> Self {
+        let mut data = bincode::serialize(&value).expect("bincode serde error");
+        data.extend("SenderKey".as_bytes());
+        Self(*keccak256(data))
+    }
+}
+
+impl From<TransactionCountKey> for MemorizerKey {
+    fn from(value: TransactionCountKey) -> Self {
+        let mut data = bincode::serialize(&value).expect("bincode serde error");
+        data.extend("TransactionCountKey".as_bytes());
+        Self(*keccak256(data))
+    }
+}
+
 impl From<ReceiptKey> for MemorizerKey {
     fn from(value: ReceiptKey) -> Self {
-        Self(*keccak256(
-            bincode::serialize(&value).expect("bincode serde error"),
-        ))
+        // `ReceiptKey` serializes exactly like `TransactionKey`, so it needs its own domain.
+        let mut data = bincode::serialize(&value).expect("bincode serde error");
+        data.extend("ReceiptKey".as_bytes());
+        Self(*keccak256(data))
+    }
+}
+
+impl From<WithdrawalKey> for MemorizerKey {
+    fn from(value: WithdrawalKey) -> Self {
+        let mut data = bincode::serialize(&value).expect("bincode serde error");
+        data.extend("WithdrawalKey".as_bytes());
+        Self(*keccak256(data))
+    }
+}
+
+impl From<TransactionHashKey> for MemorizerKey {
+    fn from(value: TransactionHashKey) -> Self {
+        let mut data = bincode::serialize(&value).expect("bincode serde error");
+        data.extend("TransactionHashKey".as_bytes());
+        Self(*keccak256(data))
+    }
+}
+
+impl From<ReceiptHashKey> for TransactionHashKey {
+    fn from(value: ReceiptHashKey) -> Self {
+        Self {
+            chain_id: value.chain_id,
+            transaction_hash: value.transaction_hash,
+        }
     }
 }
 
@@ -0,0 +1,405 @@
+use super::{
+    keys::{HeaderKey, ReceiptKey, TransactionCountKey},
+    HeaderMemorizer, Memorizer, MemorizerError, ReceiptMemorizer, TransactionMemorizer,
+};
+use crate::chain::ChainId;
+use crate::mmr::MmrHasher;
+use alloy_primitives::{Address, Bloom, BloomInput, Log, LogData, B256};
+use alloy_sol_types::SolEvent;
+use serde::{Deserialize, Serialize};
+
+/// Key for identifying the logs of a block.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// Block number containing the logs.
+    pub block_number: u64,
+}
+
+/// Key for identifying the logs of an inclusive range of blocks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogRangeKey {
+    /// Chain ID of the network.
+    pub chain_id: ChainId,
+    /// First block number of the range.
+    pub from_block: u64,
+    /// Last block number of the range, inclusive.
+    pub to_block: u64,
+}
+
+/// Filter selecting logs by emitter address and topics.
+///
+/// Every field set to `None` matches any value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogFilter {
+    /// Address of the contract that emitted the log.
+    pub address: Option<Address>,
+    /// Expected topics, by position. The first topic is the event signature for non-anonymous events.
+    pub topics: [Option<B256>; 4],
+}
+
+impl LogFilter {
+    /// Creates a new [`LogFilter`] matching every log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only matches logs emitted by `address`.
+    pub fn address(mut self, address: Address) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Only matches logs whose first topic is `signature`.
+    pub fn event_signature(mut self, signature: B256) -> Self {
+        self.topics[0] = Some(signature);
+        self
+    }
+
+    /// Only matches logs whose second topic is `topic`.
+    pub fn topic1(mut self, topic: B256) -> Self {
+        self.topics[1] = Some(topic);
+        self
+    }
+
+    /// Only matches logs whose third topic is `topic`.
+    pub fn topic2(mut self, topic: B256) -> Self {
+        self.topics[2] = Some(topic);
+        self
+    }
+
+    /// Only matches logs whose fourth topic is `topic`.
+    pub fn topic3(mut self, topic: B256) -> Self {
+        self.topics[3] = Some(topic);
+        self
+    }
+
+    /// Returns `true` if the log satisfies the filter.
+    pub fn matches(&self, log: &Log) -> bool {
+        if self.address.is_some_and(|address| address != log.address) {
+            return false;
+        }
+        let topics = log.data.topics();
+        self.topics
+            .iter()
+            .enumerate()
+            .all(|(i, expected)| match expected {
+                Some(expected) => topics.get(i) == Some(expected),
+                None => true,
+            })
+    }
+
+    /// Returns `false` if the logs bloom rules out every log matching the filter.
+    ///
+    /// A bloom filter has no false negatives, so a `false` result proves that no log of the
+    /// block matches. A `true` result may be a false positive.
+    pub fn may_match_bloom(&self, bloom: &Bloom) -> bool {
+        if *bloom == Bloom::ZERO {
+            return false;
+        }
+        self.address
+            .iter()
+            .map(|address| address.as_slice())
+            .chain(self.topics.iter().flatten().map(|topic| topic.as_slice()))
+            .all(|input| bloom.contains_input(BloomInput::Raw(input)))
+    }
+}
+
+/// A log matched by a [`LogFilter`], together with its position in the block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchedLog<T = LogData> {
+    /// Block number containing the log.
+    pub block_number: u64,
+    /// Index of the transaction whose receipt contains the log.
+    pub tx_index: u64,
+    /// Index of the log within the receipt.
+    pub log_index: u64,
+    /// The log, either raw or decoded into an event.
+    pub log: Log<T>,
+}
+
+/// Outcome of a log presence check over a range of blocks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogPresence {
+    /// No log in the range matches the filter.
+    Absent,
+    /// Every log in the range matching the filter, in block order.
+    Present(Vec<MatchedLog>),
+}
+
+/// Defines a trait for querying the logs of a block from verified receipts.
+///
+/// Every receipt of the block is read through [`ReceiptMemorizer`], and the number of receipts
+/// is bound by the proven transaction count of [`TransactionMemorizer`], so no matching log can be omitted.
+///
+/// ### Online Mode
+/// In online mode, the transaction count and all receipts of the block are fetched and memorized.
+///
+/// ### zkVM Mode
+/// In zkVM (Zero-Knowledge Virtual Machine) mode, the transaction count and every receipt are
+/// verified before the logs are filtered.
+///
+/// ### Presence over a Block Range
+/// [`LogMemorizer::get_log_presence`] first checks the verified `logs_bloom` of each header.
+/// Blocks whose bloom rules the filter out are skipped, and only bloom-positive blocks fall back
+/// to a full receipt scan.
+pub trait LogMemorizer {
+    /// Retrieves the logs of a block matching the provided [`LogFilter`].
+    fn get_logs(
+        &mut self,
+        key: LogKey,
+        filter: &LogFilter,
+    ) -> Result<Vec<MatchedLog>, MemorizerError>;
+
+    /// Retrieves the logs of a block matching the provided [`LogFilter`] and the signature of `E`,
+    /// decoded into `E`.
+    ///
+    /// Logs sharing the signature of `E` but not its layout, such as an ERC-721 `Transfer` when
+    /// `E` is the ERC-20 `Transfer`, are skipped.
+    fn get_events<E: SolEvent>(
+        &mut self,
+        key: LogKey,
+        filter: &LogFilter,
+    ) -> Result<Vec<MatchedLog<E>>, MemorizerError>;
+
+    /// Checks whether any log in the range of the provided [`LogRangeKey`] matches the [`LogFilter`].
+    ///
+    /// Fails with [`MemorizerError::InvalidBlockRange`] if the range is empty.
+    fn get_log_presence(
+        &mut self,
+        key: LogRangeKey,
+        filter: &LogFilter,
+    ) -> Result<LogPresence, MemorizerError>;
+}
+
+impl<H: MmrHasher> LogMemorizer for Memorizer<H> {
+    fn get_logs(
+        &mut self,
+        key: LogKey,
+        filter: &LogFilter,
+    ) -> Result<Vec<MatchedLog>, MemorizerError> {
+        let count = self.get_transaction_count(TransactionCountKey {
+            chain_id: key.chain_id,
+            block_number: key.block_number,
+        })?;
+
+        let mut matched = Vec::new();
+        for tx_index in 0..count {
+            let receipt = self.get_receipt(ReceiptKey {
+                chain_id: key.chain_id,
+                block_number: key.block_number,
+                transaction_index: tx_index,
+            })?;
+            for (log_index, log) in receipt.logs().iter().enumerate() {
+                if filter.matches(log) {
+                    matched.push(MatchedLog {
+                        block_number: key.block_number,
+                        tx_index,
+                        log_index: log_index as u64,
+                        log: log.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(matched)
+    }
+
+    fn get_events<E: SolEvent>(
+        &mut self,
+        key: LogKey,
+        filter: &LogFilter,
+    ) -> Result<Vec<MatchedLog<E>>, MemorizerError> {
+        let filter = if E::ANONYMOUS {
+            filter.clone()
+        } else {
+            filter.clone().event_signature(E::SIGNATURE_HASH)
+        };
+
+        Ok(self
+            .get_logs(key, &filter)?
+            .into_iter()
+            .filter_map(decode_event)
+            .collect())
+    }
+
+    fn get_log_presence(
+        &mut self,
+        key: LogRangeKey,
+        filter: &LogFilter,
+    ) -> Result<LogPresence, MemorizerError> {
+        if key.from_block > key.to_block {
+            return Err(MemorizerError::InvalidBlockRange(
+                key.from_block,
+                key.to_block,
+            ));
+        }
+
+        let mut matched = Vec::new();
+        for block_number in key.from_block..=key.to_block {
+            let header = self.get_header(HeaderKey {
+                chain_id: key.chain_id,
+                block_number,
+            })?;
+            if !filter.may_match_bloom(&header.logs_bloom) {
+                continue;
+            }
+
+            let log_key = LogKey {
+                chain_id: key.chain_id,
+                block_number,
+            };
+            matched.extend(self.get_logs(log_key, filter)?);
+        }
+
+        if matched.is_empty() {
+            Ok(LogPresence::Absent)
+        } else {
+            Ok(LogPresence::Present(matched))
+        }
+    }
+}
+
+/// Decodes a matched log into the event `E`, or returns `None` if its topics or data do not fit `E`.
+fn decode_event<E: SolEvent>(matched: MatchedLog) -> Option<MatchedLog<E>> {
+    let event = E::decode_log_data(&matched.log.data, true).ok()?;
+    Some(MatchedLog {
+        block_number: matched.block_number,
+        tx_index: matched.tx_index,
+        log_index: matched.log_index,
+        log: Log {
+            address: matched.log.address,
+            data: event,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::get_rpc_urls;
+    use alloy_primitives::{address, b256, Bytes, U256};
+    use alloy_sol_types::sol;
+
+    sol! {
+        event Transfer(address indexed from, address indexed to, uint256 value);
+    }
+
+    fn transfer_log(emitter: Address, from: Address, to: Address, value: u64) -> Log {
+        Log::new_unchecked(
+            emitter,
+            vec![Transfer::SIGNATURE_HASH, from.into_word(), to.into_word()],
+            Bytes::from(U256::from(value).to_be_bytes_vec()),
+        )
+    }
+
+    #[test]
+    fn test_log_filter_matches() {
+        let token = address!("7f2c6f930306d3aa736b3a6c6a98f512f74036d4");
+        let alice = address!("75cec1db9dceb703200eaa6595f66885c962b920");
+        let bob = address!("0000000000000000000000000000000000000002");
+        let log = transfer_log(token, alice, bob, 10);
+
+        assert!(LogFilter::new().matches(&log));
+        assert!(LogFilter::new()
+            .address(token)
+            .event_signature(Transfer::SIGNATURE_HASH)
+            .topic1(alice.into_word())
+            .matches(&log));
+        assert!(!LogFilter::new().address(alice).matches(&log));
+        assert!(!LogFilter::new().topic2(alice.into_word()).matches(&log));
+        assert!(!LogFilter::new()
+            .topic3(b256!(
+                "0000000000000000000000000000000000000000000000000000000000000001"
+            ))
+            .matches(&log));
+    }
+
+    #[test]
+    fn test_log_filter_bloom() {
+        let token = address!("7f2c6f930306d3aa736b3a6c6a98f512f74036d4");
+        let alice = address!("75cec1db9dceb703200eaa6595f66885c962b920");
+        let bob = address!("0000000000000000000000000000000000000002");
+        let mut bloom = Bloom::ZERO;
+        bloom.accrue_log(&transfer_log(token, alice, bob, 10));
+
+        assert!(!LogFilter::new().may_match_bloom(&Bloom::ZERO));
+        assert!(LogFilter::new().may_match_bloom(&bloom));
+        assert!(LogFilter::new()
+            .address(token)
+            .event_signature(Transfer::SIGNATURE_HASH)
+            .topic2(bob.into_word())
+            .may_match_bloom(&bloom));
+        assert!(!LogFilter::new().address(alice).may_match_bloom(&bloom));
+        assert!(!LogFilter::new()
+            .address(token)
+            .topic1(token.into_word())
+            .may_match_bloom(&bloom));
+    }
+
+    #[test]
+    fn test_decode_event_skips_other_layouts() {
+        let token = address!("7f2c6f930306d3aa736b3a6c6a98f512f74036d4");
+        let alice = address!("75cec1db9dceb703200eaa6595f66885c962b920");
+        let bob = address!("0000000000000000000000000000000000000002");
+        let erc20 = transfer_log(token, alice, bob, 10);
+        // ERC-721 `Transfer` indexes the token id, which leaves no data
+        let erc721 = Log::new_unchecked(
+            token,
+            vec![
+                Transfer::SIGNATURE_HASH,
+                alice.into_word(),
+                bob.into_word(),
+                U256::from(7).into(),
+            ],
+            Bytes::new(),
+        );
+        let matched = |log_index, log| MatchedLog {
+            block_number: 1,
+            tx_index: 0,
+            log_index,
+            log,
+        };
+
+        let event = decode_event::<Transfer>(matched(0, erc20)).unwrap();
+        assert_eq!(event.log_index, 0);
+        assert_eq!(event.log.address, token);
+        assert_eq!(event.log.data.from, alice);
+        assert_eq!(event.log.data.to, bob);
+        assert_eq!(event.log.data.value, U256::from(10));
+        assert!(decode_event::<Transfer>(matched(1, erc721)).is_none());
+    }
+
+    #[test]
+    fn test_get_events() {
+        let key = LogKey {
+            chain_id: ChainId::EthereumSepolia,
+            block_number: 5_244_652,
+        };
+        let mut memorizer = Memorizer::new(get_rpc_urls(), "ETHEREUM_SEPOLIA");
+        let events = memorizer
+            .get_events::<Transfer>(key.clone(), &LogFilter::new())
+            .unwrap();
+        let logs = memorizer
+            .get_logs(
+                key,
+                &LogFilter::new().event_signature(Transfer::SIGNATURE_HASH),
+            )
+            .unwrap();
+
+        // Every decoded event is a matched log, and every log of the ERC-20 layout is decoded
+        let erc20_logs: Vec<&MatchedLog> = logs
+            .iter()
+            .filter(|matched| matched.log.data.topics().len() == 3)
+            .collect();
+        assert_eq!(events.len(), erc20_logs.len());
+        for (event, log) in events.iter().zip(erc20_logs) {
+            assert_eq!(
+                (event.tx_index, event.log_index),
+                (log.tx_index, log.log_index)
+            );
+            assert_eq!(event.log.data.encode_log_data(), log.log.data);
+        }
+    }
+}
@@ -2,32 +2,47 @@
 pub mod account;
 /// consensus layer header memorizer
 pub mod cl_header;
+/// code memorizer
+pub mod code;
 /// header memorizer
 pub mod header;
 /// memorizer keys
 pub mod keys;
+/// log queries over verified receipts
+pub mod log;
 /// receipt memorizer
 pub mod receipt;
+/// newer MMR snapshots proven consistent with the memorized one
+pub mod snapshot;
 /// storage memorizer
 pub mod storage;
 /// transaction memorizer
 pub mod transaction;
+/// joined transaction and receipt view
+pub mod transaction_full;
 /// memorizer values
 pub mod values;
+/// withdrawal memorizer
+pub mod withdrawal;
 
 pub use account::*;
 use alloy_sol_types::sol;
 pub use cl_header::*;
+pub use code::*;
 pub use header::*;
 pub use keys::*;
+pub use log::*;
 pub use receipt::*;
+pub use snapshot::*;
 pub use storage::*;
 pub use transaction::*;
+pub use transaction_full::*;
 pub use values::*;
+pub use withdrawal::*;
 
 use crate::{
     chain::ChainId,
-    mmr::{MmrError, MmrMeta},
+    mmr::{KeccakHasher, MmrError, MmrHasher, MmrMeta},
     mpt::MptError,
 };
 use core::str::FromStr;
@@ -51,15 +66,20 @@ sol! {
 }
 
 /// Represents a main structure for managing and memorizing various components such as headers, accounts, and receipts.
+///
+/// Headers are proven against MMRs hashed with `H`, keccak by default.
 #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
-pub struct Memorizer {
+#[serde(bound = "")]
+pub struct Memorizer<H: MmrHasher = KeccakHasher> {
     /// Maps chain IDs to their respective RPC URLs.
     #[serde(skip)]
     pub chain_map: HashMap<ChainId, Url>,
     /// Target chain ID for verification.
     pub to_chain_id: ChainId,
     /// Metadata for the Merkle Mountain Range (MMR).
-    pub mmr_meta: HashMap<ChainId, MmrMeta>,
+    pub mmr_meta: HashMap<ChainId, MmrMeta<H>>,
+    /// Newer MMR snapshots, with a boolean flag for an already verified consistency proof.
+    pub newer_mmr_meta: HashMap<ChainId, (NewerSnapshot<H>, bool)>,
     /// Maps memorizer keys to their values and a boolean flag for is already verified value.
     pub map: HashMap<MemorizerKey, (MemorizerValue, bool)>,
 }
@@ -67,10 +87,18 @@ pub struct Memorizer {
 impl Memorizer {
     /// Creates a new [`Memorizer`] instance.
     pub fn new<S: AsRef<str>>(chain_map: HashMap<ChainId, Url>, to_chain_id: S) -> Self {
+        Self::with_hasher(chain_map, to_chain_id)
+    }
+}
+
+impl<H: MmrHasher> Memorizer<H> {
+    /// Creates a new [`Memorizer`] instance proving headers against MMRs hashed with `H`.
+    pub fn with_hasher<S: AsRef<str>>(chain_map: HashMap<ChainId, Url>, to_chain_id: S) -> Self {
         Self {
             chain_map,
             to_chain_id: ChainId::from_str(to_chain_id.as_ref()).unwrap(),
             mmr_meta: Default::default(),
+            newer_mmr_meta: Default::default(),
             map: Default::default(),
         }
     }
@@ -83,14 +111,58 @@ pub enum MemorizerError {
     #[error("Header is missing or invalid")]
     MissingHeader,
 
+    /// Indicates that no pair of consecutive headers covers the requested timestamp.
+    #[error("No header covers the requested timestamp")]
+    InvalidTimestamp,
+
+    /// Indicates headers that do not link a block to its anchor block by parent hashes.
+    #[error("Header chain does not link to the anchor header")]
+    InvalidHeaderChain,
+
+    /// Indicates a missing newer MMR snapshot in the memorizer.
+    #[error("Newer MMR snapshot is missing")]
+    MissingSnapshot,
+
+    /// Indicates a header reached by a header chain among headers proven by an MMR multi-proof.
+    #[error("Header of block {0} was reached by a header chain and has no MMR proof to batch")]
+    ChainedHeaderInBatch(u64),
+
     /// Indicates a missing or invalid account in the memorizer.
     #[error("Account is missing or invalid")]
     MissingAccount,
 
+    /// Indicates an account creation block that lies after the block bounding the search.
+    #[error("Account creation block is invalid")]
+    InvalidAccountCreation,
+
     /// Indicates a missing or invalid storage entry in the memorizer.
     #[error("Storage is missing or invalid")]
     MissingStorage,
 
+    /// Indicates a storage change point that does not hold.
+    #[error("Storage change point is invalid")]
+    InvalidStorageChange,
+
+    /// Indicates a dynamically-sized storage value longer than the readable maximum.
+    #[error("Storage value length {0} exceeds the maximum")]
+    StorageLengthTooLarge(alloy_primitives::U256),
+
+    /// Indicates a storage location larger than the type it is decoded into.
+    #[error("Storage location of {0} bytes does not fit a {1}-byte value")]
+    StorageSizeMismatch(usize, usize),
+
+    /// Indicates a storage string that is not valid UTF-8.
+    #[error(transparent)]
+    InvalidStorageString(#[from] std::string::FromUtf8Error),
+
+    /// Indicates missing account bytecode in the memorizer.
+    #[error("Code is missing")]
+    MissingCode,
+
+    /// Indicates account bytecode that does not match the account's code hash.
+    #[error("Code does not match the account code hash")]
+    InvalidCode,
+
     /// Indicates a missing or invalid transaction in the memorizer.
     #[error("Transaction is missing or invalid")]
     MissingTransaction,
@@ -99,6 +171,18 @@ pub enum MemorizerError {
     #[error("Receipt is missing or invalid")]
     MissingReceipt,
 
+    /// Indicates a missing or invalid withdrawal in the memorizer.
+    #[error("Withdrawal is missing or invalid")]
+    MissingWithdrawal,
+
+    /// Indicates that the proven transaction does not hash to the requested transaction hash.
+    #[error("Transaction hash mismatch")]
+    TransactionHashMismatch,
+
+    /// Indicates a failure in recovering the sender from a transaction signature.
+    #[error(transparent)]
+    SignerRecoveryFailed(#[from] alloy_primitives::SignatureError),
+
     /// Indicates a missing consensus layer beacon header in the memorizer.
     #[error("Beacon header is missing")]
     MissingBeaconRoot,
@@ -123,6 +207,10 @@ pub enum MemorizerError {
     #[error(transparent)]
     MmrProofFailed(#[from] MmrError),
 
+    /// Represents an error in decoding a log into a Solidity event.
+    #[error(transparent)]
+    EventDecodeFailed(#[from] alloy_sol_types::Error),
+
     /// Represents an error in decoding RLP data.
     #[error(transparent)]
     RlpDecodeFailed(#[from] alloy_rlp::Error),
@@ -137,11 +225,20 @@ pub enum MemorizerError {
     #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
 
+    /// Represents an error in fetching headers in batch from the Indexer.
+    #[cfg(not(target_os = "zkvm"))]
+    #[error(transparent)]
+    IndexerError(#[from] crate::header::IndexerError),
+
     /// Indicates an error in Ethereum trie proof verification.
     #[cfg(not(target_os = "zkvm"))]
     #[error(transparent)]
     EthTrieError(#[from] eth_trie_proofs::EthTrieError),
 
+    /// Indicates a block range whose first block comes after its last block.
+    #[error("Invalid block range: {0} > {1}")]
+    InvalidBlockRange(u64, u64),
+
     /// Indicates that the given block number belongs to the pre-PoS (Proof of Stake) era.
     #[error("The given execution layer block number was produced before the PoS transition")]
     InvalidPoSBlockNumber,
@@ -193,4 +290,51 @@ mod tests {
 
         assert_eq!(original_mem, mem);
     }
+
+    #[test]
+    fn test_get_headers_rejects_chained_header() {
+        let chain_id = ChainId::EthereumSepolia;
+        let mut memorizer = Memorizer::new(HashMap::default(), "ETHEREUM_SEPOLIA");
+        // Headers reached by a header chain are memorized with an element index of 0
+        memorizer.map.insert(
+            HeaderKey {
+                chain_id,
+                block_number: 7,
+            }
+            .into(),
+            (
+                MemorizerValue::Header(HeaderMemorizerValue::default()),
+                false,
+            ),
+        );
+
+        assert!(matches!(
+            memorizer.get_headers(HeadersKey {
+                chain_id,
+                block_numbers: vec![7],
+            }),
+            Err(MemorizerError::ChainedHeaderInBatch(7))
+        ));
+    }
+
+    #[test]
+    fn test_advance_snapshot() {
+        let chain_id = ChainId::EthereumSepolia;
+        let mut memorizer = Memorizer::new(crate::utils::get_rpc_urls(), "ETHEREUM_SEPOLIA");
+        memorizer
+            .get_header(HeaderKey {
+                chain_id,
+                block_number: 665200,
+            })
+            .unwrap();
+        let root_hash = memorizer.advance_snapshot(chain_id).unwrap();
+
+        // The newer snapshot is memorized unverified, next to the one the header is proven against
+        let (newer, is_verified) = memorizer.newer_mmr_meta.get(&chain_id).unwrap();
+        assert!(!is_verified);
+        assert_eq!(newer.meta.root_hash, root_hash);
+        assert!(memorizer.mmr_meta[&chain_id]
+            .verify_consistency(&newer.meta, &newer.proof)
+            .unwrap());
+    }
 }
@@ -1,6 +1,14 @@
-use super::{keys::ReceiptKey, MemorizerError};
+use super::{
+    keys::{ReceiptHashKey, ReceiptKey},
+    MemorizerError,
+};
 use alloy_consensus::ReceiptEnvelope;
 use cfg_if::cfg_if;
+#[cfg(any(target_os = "zkvm", test))]
+use {
+    super::values::ReceiptMemorizerValue, crate::mpt::Mpt, alloy_eips::eip2718::Encodable2718,
+    alloy_primitives::B256, alloy_rlp::Decodable,
+};
 
 /// Defines a trait for managing and retrieving transaction receipts from the memorizer.
 ///
@@ -16,9 +24,44 @@ use cfg_if::cfg_if;
 ///   it undergoes verification.
 /// - In both cases, if the `is_verified` flag is `true`, the memorized header or receipt
 ///   is read directly without additional verification.
+///
+/// ### Lookup by Hash
+/// Receipts do not commit to the hash of their transaction, so a lookup by hash also memorizes
+/// the transaction at the same position. In zkVM mode, that transaction must hash to the
+/// requested transaction hash before the receipt is returned.
 pub trait ReceiptMemorizer {
     /// Retrieves a transaction receipt based on the provided [`ReceiptKey`].
     fn get_receipt(&mut self, key: ReceiptKey) -> Result<ReceiptEnvelope, MemorizerError>;
+
+    /// Retrieves a transaction receipt based on the provided [`ReceiptHashKey`].
+    fn get_receipt_by_hash(
+        &mut self,
+        key: ReceiptHashKey,
+    ) -> Result<ReceiptEnvelope, MemorizerError>;
+}
+
+/// Verifies a memorized receipt against the receipts root at the index of its key.
+///
+/// The index memorized with the receipt is supplied by the prover, so it must match the
+/// requested index: otherwise a receipt proven at another index could be served in place
+/// of the requested one.
+#[cfg(any(target_os = "zkvm", test))]
+fn verify_receipt_value(
+    receipt_root: B256,
+    transaction_index: u64,
+    receipt_value: &ReceiptMemorizerValue,
+) -> Result<ReceiptEnvelope, MemorizerError> {
+    if receipt_value.tx_index != transaction_index {
+        return Err(MemorizerError::MissingReceipt);
+    }
+    let receipt = ReceiptEnvelope::decode(&mut receipt_value.receipt_encoded.as_ref())?;
+    let mpt = Mpt { root: receipt_root };
+    mpt.verify_receipt(
+        transaction_index,
+        &receipt.encoded_2718(),
+        receipt_value.proof.clone(),
+    )?;
+    Ok(receipt)
 }
 
 cfg_if! {
@@ -28,3 +71,43 @@ cfg_if! {
         mod online;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpt::ordered_trie_with_proof;
+    use alloy_consensus::{Receipt, ReceiptWithBloom};
+    use alloy_primitives::Bytes;
+
+    #[test]
+    fn test_verify_receipt_value_index() {
+        let encoded: Vec<Bytes> = (1..=3)
+            .map(|i| {
+                let receipt = ReceiptEnvelope::Legacy(ReceiptWithBloom {
+                    receipt: Receipt {
+                        status: true.into(),
+                        cumulative_gas_used: 21_000 * i,
+                        logs: Vec::new(),
+                    },
+                    logs_bloom: Default::default(),
+                });
+                receipt.encoded_2718().into()
+            })
+            .collect();
+        let (receipt_root, proof) = ordered_trie_with_proof(&encoded, 1);
+        let receipt_value = ReceiptMemorizerValue {
+            receipt_encoded: encoded[1].clone(),
+            tx_index: 1,
+            proof,
+        };
+
+        let receipt = verify_receipt_value(receipt_root, 1, &receipt_value).unwrap();
+        assert_eq!(Bytes::from(receipt.encoded_2718()), encoded[1]);
+
+        // Receipt 1, proven at its own index, cannot be served as receipt 0
+        assert!(matches!(
+            verify_receipt_value(receipt_root, 0, &receipt_value),
+            Err(MemorizerError::MissingReceipt)
+        ));
+    }
+}
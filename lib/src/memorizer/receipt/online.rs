@@ -1,12 +1,16 @@
 use super::ReceiptMemorizer;
-use crate::memorizer::{HeaderKey, HeaderMemorizer, ReceiptMemorizerValue};
+use crate::memorizer::{
+    HeaderKey, HeaderMemorizer, ReceiptHashKey, ReceiptKey, ReceiptMemorizerValue,
+    TransactionMemorizer,
+};
 use crate::memorizer::{Memorizer, MemorizerError};
+use crate::mmr::MmrHasher;
 use crate::transaction::{ReceiptResponse, TransactionClient};
 use alloy_consensus::ReceiptEnvelope;
 use alloy_rlp::Encodable;
 use tokio::runtime::Runtime;
 
-impl ReceiptMemorizer for Memorizer {
+impl<H: MmrHasher> ReceiptMemorizer for Memorizer<H> {
     fn get_receipt(
         &mut self,
         key: crate::memorizer::keys::ReceiptKey,
@@ -49,4 +53,19 @@ impl ReceiptMemorizer for Memorizer {
 
         Ok(tx)
     }
+
+    fn get_receipt_by_hash(
+        &mut self,
+        key: ReceiptHashKey,
+    ) -> Result<ReceiptEnvelope, MemorizerError> {
+        let tx_key = self.locate_transaction(key.into())?;
+        let receipt_key = ReceiptKey {
+            chain_id: tx_key.chain_id,
+            block_number: tx_key.block_number,
+            transaction_index: tx_key.transaction_index,
+        };
+        // The transaction is memorized so the zkVM can bind the receipt to the requested hash.
+        let _ = self.get_transaction(tx_key)?;
+        self.get_receipt(receipt_key)
+    }
 }
@@ -1,17 +1,14 @@
-use super::ReceiptMemorizer;
+use super::{verify_receipt_value, ReceiptMemorizer};
 use crate::memorizer::{
-    keys::HeaderKey, keys::MemorizerKey, values::MemorizerValue, HeaderMemorizer, Memorizer,
-    MemorizerError,
+    keys::HeaderKey, keys::MemorizerKey, keys::ReceiptHashKey, keys::ReceiptKey,
+    values::MemorizerValue, HeaderMemorizer, Memorizer, MemorizerError, TransactionMemorizer,
 };
-use crate::mpt::Mpt;
+use crate::mmr::MmrHasher;
 use alloy_consensus::ReceiptEnvelope;
 use alloy_rlp::Decodable;
 
-impl ReceiptMemorizer for Memorizer {
-    fn get_receipt(
-        &mut self,
-        key: crate::memorizer::keys::ReceiptKey,
-    ) -> Result<ReceiptEnvelope, MemorizerError> {
+impl<H: MmrHasher> ReceiptMemorizer for Memorizer<H> {
+    fn get_receipt(&mut self, key: ReceiptKey) -> Result<ReceiptEnvelope, MemorizerError> {
         // 1. Header
         let header_key = HeaderKey {
             block_number: key.block_number,
@@ -21,6 +18,7 @@ impl ReceiptMemorizer for Memorizer {
 
         // 2. Receipt
         let receipt_root = header.receipts_root;
+        let transaction_index = key.transaction_index;
         let receipt_key: MemorizerKey = key.into();
 
         if let Some((MemorizerValue::Receipt(receipt_value), is_verified)) =
@@ -28,19 +26,39 @@ impl ReceiptMemorizer for Memorizer {
         {
             if *is_verified {
                 println!("Receipt MPT already verified");
-                let tx_encoded = receipt_value.receipt_encoded.clone();
-                Ok(ReceiptEnvelope::decode(&mut tx_encoded.as_ref())?)
+                Ok(ReceiptEnvelope::decode(
+                    &mut receipt_value.receipt_encoded.as_ref(),
+                )?)
             } else {
-                let mpt = Mpt { root: receipt_root };
                 println!("cycle-tracker-start: mpt (receipt)");
-                mpt.verify_receipt(receipt_value.tx_index, receipt_value.proof.clone())?;
+                let receipt = verify_receipt_value(receipt_root, transaction_index, receipt_value)?;
                 println!("cycle-tracker-end: mpt (receipt)");
                 *is_verified = true;
-                let tx_encoded = receipt_value.receipt_encoded.clone();
-                Ok(ReceiptEnvelope::decode(&mut tx_encoded.as_ref())?)
+                Ok(receipt)
             }
         } else {
             Err(MemorizerError::MissingReceipt)
         }
     }
+
+    fn get_receipt_by_hash(
+        &mut self,
+        key: ReceiptHashKey,
+    ) -> Result<ReceiptEnvelope, MemorizerError> {
+        let tx_hash = key.transaction_hash;
+        let tx_key = self.locate_transaction(key.into())?;
+        let receipt_key = ReceiptKey {
+            chain_id: tx_key.chain_id,
+            block_number: tx_key.block_number,
+            transaction_index: tx_key.transaction_index,
+        };
+
+        // Receipts do not commit to their transaction hash, so bind the position through the transaction.
+        let tx = self.get_transaction(tx_key)?;
+        if *tx.tx_hash() != tx_hash {
+            return Err(MemorizerError::TransactionHashMismatch);
+        }
+
+        self.get_receipt(receipt_key)
+    }
 }
@@ -0,0 +1,48 @@
+use super::MemorizerError;
+use crate::{
+    chain::ChainId,
+    mmr::{KeccakHasher, MmrConsistencyProof, MmrHasher, MmrMeta},
+};
+use alloy_primitives::B256;
+use cfg_if::cfg_if;
+use serde::{Deserialize, Serialize};
+
+/// Defines a trait for moving the MMR snapshot committed by a program to a newer one.
+///
+/// Headers are proven against the snapshot served with them, which the Indexer keeps growing.
+/// A proof that this snapshot is a prefix of a newer one lets the program commit the newer
+/// root, without fetching every header proof again.
+///
+/// ### Online Mode
+/// In online mode, the current snapshot of the Indexer is fetched with a consistency proof built
+/// from the inclusion proofs of a leaf of every older mountain, found from any memorized header
+/// of the chain. It is memorized next to the snapshot the headers are proven against, which is
+/// left unchanged, so it must be requested once every header of the chain is memorized.
+///
+/// ### zkVM Mode
+/// In zkVM mode, the consistency proof is verified against the snapshot the headers are proven
+/// against. Once verified, `hdp_main` commits the newer snapshot instead.
+pub trait SnapshotMemorizer {
+    /// Proves that the MMR snapshot of `chain_id` is a prefix of a newer snapshot, and returns
+    /// the root of the newer snapshot.
+    fn advance_snapshot(&mut self, chain_id: ChainId) -> Result<B256, MemorizerError>;
+}
+
+cfg_if! {
+    if #[cfg(target_os = "zkvm")] {
+        mod zkvm;
+    } else {
+        mod online;
+    }
+}
+
+/// A newer snapshot of an MMR, with the proof that the snapshot headers are proven against is a
+/// prefix of it.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "")]
+pub struct NewerSnapshot<H: MmrHasher = KeccakHasher> {
+    /// Metadata of the newer snapshot.
+    pub meta: MmrMeta<H>,
+    /// Proof that the older snapshot is a prefix of the newer one.
+    pub proof: MmrConsistencyProof,
+}
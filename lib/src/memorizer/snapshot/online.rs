@@ -0,0 +1,59 @@
+use super::{NewerSnapshot, SnapshotMemorizer};
+use crate::memorizer::{
+    keys::{HeaderKey, MemorizerKey},
+    values::MemorizerValue,
+    Memorizer, MemorizerError,
+};
+use crate::{chain::ChainId, mmr::MmrHasher, provider::header::IndexerClient};
+use alloy_primitives::B256;
+use tokio::runtime::Runtime;
+
+impl<H: MmrHasher> SnapshotMemorizer for Memorizer<H> {
+    fn advance_snapshot(&mut self, chain_id: ChainId) -> Result<B256, MemorizerError> {
+        let older = self
+            .mmr_meta
+            .get(&chain_id)
+            .ok_or(MemorizerError::MissingHeader)?;
+
+        // Any header of the chain proven against the MMR anchors its leaves to block numbers.
+        let anchor_block_number = self
+            .map
+            .iter()
+            .find_map(|(key, (value, _))| match value {
+                MemorizerValue::Header(header_value)
+                    if header_value.element_index != 0
+                        && *key
+                            == MemorizerKey::from(HeaderKey {
+                                chain_id,
+                                block_number: header_value.header.number,
+                            }) =>
+                {
+                    Some(header_value.header.number)
+                }
+                _ => None,
+            })
+            .ok_or(MemorizerError::MissingHeader)?;
+
+        let rt = Runtime::new()?;
+        let consistency = rt.block_on(async {
+            let client = IndexerClient::new(chain_id, self.to_chain_id).with_hasher::<H>();
+            client
+                .get_consistency_proof(older, anchor_block_number)
+                .await
+                .map_err(MemorizerError::IndexerError)
+        })?;
+        let root_hash = consistency.meta.root_hash;
+
+        self.newer_mmr_meta.insert(
+            chain_id,
+            (
+                NewerSnapshot {
+                    meta: consistency.meta,
+                    proof: consistency.proof,
+                },
+                false,
+            ),
+        );
+        Ok(root_hash)
+    }
+}
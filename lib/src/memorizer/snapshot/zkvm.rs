@@ -0,0 +1,32 @@
+use super::SnapshotMemorizer;
+use crate::memorizer::{Memorizer, MemorizerError};
+use crate::{chain::ChainId, mmr::MmrHasher};
+use alloy_primitives::B256;
+
+impl<H: MmrHasher> SnapshotMemorizer for Memorizer<H> {
+    fn advance_snapshot(&mut self, chain_id: ChainId) -> Result<B256, MemorizerError> {
+        let older = self
+            .mmr_meta
+            .get_mut(&chain_id)
+            .ok_or(MemorizerError::MissingHeader)?;
+        let (newer, is_verified) = self
+            .newer_mmr_meta
+            .get_mut(&chain_id)
+            .ok_or(MemorizerError::MissingSnapshot)?;
+
+        if *is_verified {
+            println!("MMR snapshot consistency already verified");
+            return Ok(newer.meta.root_hash);
+        }
+        if !older.peaks_validated() {
+            println!("cycle-tracker-start: mmr_peaks");
+            older.validate_peaks()?;
+            println!("cycle-tracker-end: mmr_peaks");
+        }
+        println!("cycle-tracker-start: mmr_consistency");
+        older.verify_consistency(&newer.meta, &newer.proof)?;
+        println!("cycle-tracker-end: mmr_consistency");
+        *is_verified = true;
+        Ok(newer.meta.root_hash)
+    }
+}
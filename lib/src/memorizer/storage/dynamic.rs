@@ -0,0 +1,184 @@
+use super::StorageMemorizer;
+use crate::memorizer::{
+    keys::{StorageKey, StoragesKey},
+    Memorizer, MemorizerError,
+};
+use crate::mmr::MmrHasher;
+use crate::storage_layout::{StorageSlot, StorageValue};
+use alloy_primitives::{Bytes, B256, U256};
+use std::collections::HashMap;
+
+/// Maximum number of data slots read for a single dynamically-sized value.
+///
+/// Lengths are read from storage, so they are capped before any slot is allocated or fetched.
+pub const MAX_DYNAMIC_STORAGE_SLOTS: u64 = 1 << 12;
+
+/// Defines a trait for reading dynamically-sized Solidity values from storage.
+///
+/// The [`StorageKey`] points to the slot holding the length of the value, as laid out by `solc`:
+/// - A `bytes` or `string` of at most 31 bytes is stored in that slot, together with its length.
+/// - A longer `bytes` or `string` stores its length there, and its data from `keccak256(slot)` on.
+/// - A `T[]` stores its length there, and its elements from `keccak256(slot)` on.
+///
+/// Values spanning more than [`MAX_DYNAMIC_STORAGE_SLOTS`] data slots are rejected.
+///
+/// ### Online Mode
+/// In online mode, the length slot is read first. All data slots are then fetched with a single
+/// `eth_getProof` request and memorized.
+///
+/// ### zkVM Mode
+/// In zkVM (Zero-Knowledge Virtual Machine) mode, the length slot and every data slot are verified
+/// as in [`StorageMemorizer`].
+pub trait DynamicStorageMemorizer {
+    /// Retrieves a Solidity `bytes` value based on the provided [`StorageKey`].
+    fn get_storage_bytes(&mut self, key: StorageKey) -> Result<Bytes, MemorizerError>;
+
+    /// Retrieves a Solidity `string` value based on the provided [`StorageKey`].
+    fn get_storage_string(&mut self, key: StorageKey) -> Result<String, MemorizerError>;
+
+    /// Retrieves the elements of a Solidity `T[]` value based on the provided [`StorageKey`].
+    fn get_storage_array<T: StorageValue>(
+        &mut self,
+        key: StorageKey,
+    ) -> Result<Vec<T>, MemorizerError>;
+}
+
+impl<H: MmrHasher> DynamicStorageMemorizer for Memorizer<H> {
+    fn get_storage_bytes(&mut self, key: StorageKey) -> Result<Bytes, MemorizerError> {
+        let word = self.get_storage(key.clone())?;
+        let len = match decode_bytes_slot(word)? {
+            BytesSlot::Short(bytes) => return Ok(bytes),
+            BytesSlot::Long(len) => len,
+        };
+
+        let data = StorageSlot::from(key.storage_slot).data();
+        let storage_slots: Vec<B256> = (0..len.div_ceil(32) as u64)
+            .map(|i| data.offset(i).into())
+            .collect();
+        let words = self.get_storages(StoragesKey {
+            chain_id: key.chain_id,
+            block_number: key.block_number,
+            address: key.address,
+            storage_slots,
+        })?;
+
+        let mut bytes: Vec<u8> = words
+            .iter()
+            .flat_map(|word| word.to_be_bytes::<32>())
+            .collect();
+        bytes.truncate(len);
+        Ok(bytes.into())
+    }
+
+    fn get_storage_string(&mut self, key: StorageKey) -> Result<String, MemorizerError> {
+        let bytes = self.get_storage_bytes(key)?;
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    fn get_storage_array<T: StorageValue>(
+        &mut self,
+        key: StorageKey,
+    ) -> Result<Vec<T>, MemorizerError> {
+        let word = self.get_storage(key.clone())?;
+        let per_slot = (32 / T::SIZE.max(1)).max(1) as u64;
+        let len = match u64::try_from(word) {
+            Ok(len) if len.div_ceil(per_slot) <= MAX_DYNAMIC_STORAGE_SLOTS => len,
+            _ => return Err(MemorizerError::StorageLengthTooLarge(word)),
+        };
+
+        let base = StorageSlot::from(key.storage_slot);
+        let locations: Vec<_> = (0..len).map(|i| base.array_element(i, T::SIZE)).collect();
+        // Small elements share slots, so each slot is only fetched once.
+        let mut storage_slots: Vec<B256> = locations
+            .iter()
+            .map(|location| location.slot.into())
+            .collect();
+        storage_slots.dedup();
+
+        let words = self.get_storages(StoragesKey {
+            chain_id: key.chain_id,
+            block_number: key.block_number,
+            address: key.address,
+            storage_slots: storage_slots.clone(),
+        })?;
+        let words: HashMap<B256, U256> = storage_slots.into_iter().zip(words).collect();
+
+        locations
+            .iter()
+            .map(|location| location.decode(words[&B256::from(location.slot)]))
+            .collect()
+    }
+}
+
+/// The content of the length slot of a Solidity `bytes` or `string`.
+#[derive(Debug, PartialEq)]
+enum BytesSlot {
+    /// A value of at most 31 bytes, stored in the slot itself.
+    Short(Bytes),
+    /// The length of a longer value, stored from `keccak256(slot)` on.
+    Long(usize),
+}
+
+/// Decodes the length slot of a Solidity `bytes` or `string`.
+///
+/// Short values store `len * 2` in the lowest-order byte, long values store `len * 2 + 1`.
+fn decode_bytes_slot(word: U256) -> Result<BytesSlot, MemorizerError> {
+    if word.bit(0) {
+        let len = word >> 1;
+        if len > U256::from(MAX_DYNAMIC_STORAGE_SLOTS * 32) {
+            return Err(MemorizerError::StorageLengthTooLarge(len));
+        }
+        Ok(BytesSlot::Long(len.to::<usize>()))
+    } else {
+        let len = ((word.byte(0) / 2) as usize).min(31);
+        Ok(BytesSlot::Short(Bytes::copy_from_slice(
+            &word.to_be_bytes::<32>()[..len],
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::uint;
+
+    #[test]
+    fn test_decode_bytes_slot() {
+        // "hello" stored in place, with 5 * 2 in the lowest-order byte
+        let word = uint!(0x68656c6c6f00000000000000000000000000000000000000000000000000000a_U256);
+        assert_eq!(
+            decode_bytes_slot(word).unwrap(),
+            BytesSlot::Short(Bytes::from_static(b"hello"))
+        );
+
+        assert_eq!(
+            decode_bytes_slot(U256::ZERO).unwrap(),
+            BytesSlot::Short(Bytes::new())
+        );
+        assert_eq!(
+            decode_bytes_slot(U256::from(65)).unwrap(),
+            BytesSlot::Long(32)
+        );
+        assert_eq!(
+            decode_bytes_slot(U256::from(201)).unwrap(),
+            BytesSlot::Long(100)
+        );
+    }
+
+    #[test]
+    fn test_decode_bytes_slot_too_long() {
+        let max_len = MAX_DYNAMIC_STORAGE_SLOTS * 32;
+        assert_eq!(
+            decode_bytes_slot(U256::from(max_len * 2 + 1)).unwrap(),
+            BytesSlot::Long(max_len as usize)
+        );
+        assert!(matches!(
+            decode_bytes_slot(U256::from(max_len * 2 + 3)),
+            Err(MemorizerError::StorageLengthTooLarge(_))
+        ));
+        assert!(matches!(
+            decode_bytes_slot(U256::MAX),
+            Err(MemorizerError::StorageLengthTooLarge(_))
+        ));
+    }
+}
@@ -1,7 +1,13 @@
-use super::{keys::StorageKey, MemorizerError};
+use super::{
+    keys::{StorageChangeKey, StorageKey, StoragesKey},
+    MemorizerError,
+};
 use alloy_primitives::U256;
 use cfg_if::cfg_if;
 
+mod dynamic;
+pub use dynamic::*;
+
 /// Defines a trait for managing and retrieving storage values from the memorizer.
 ///
 /// ### Online Mode
@@ -16,9 +22,45 @@ use cfg_if::cfg_if;
 /// - After verifying the header, the account is checked similarly, and if not verified, it undergoes verification.
 /// - Finally, the storage is checked and verified if needed.
 /// - If any element (header, account, or storage) has an `is_verified` flag of `true`, it is read directly from the memorizer without re-verification.
+///
+/// ### Batch Reads
+/// In online mode, [`StorageMemorizer::get_storages`] fetches every slot with a single `eth_getProof`
+/// request, sharing one account proof. In zkVM mode, each slot is verified as with `get_storage`.
+///
+/// ### Change Point Search
+/// In online mode, [`StorageMemorizer::get_storage_change`] walks the range back from its end to the
+/// last block `N` such that the slot differs at `N - 1`, memorizing the storage at every block from
+/// `N - 1` to the end of the range.
+/// In zkVM mode, these storage proofs are verified: the slot must hold its end value at every block
+/// from `N` on, and another value at `N - 1`, which proves that `N` is the last change. If the slot
+/// did not change, the storage at every block of the range is verified.
 pub trait StorageMemorizer {
     /// Retrieves a storage value based on the provided [`StorageKey`].
     fn get_storage(&mut self, key: StorageKey) -> Result<U256, MemorizerError>;
+
+    /// Retrieves several storage values of one account based on the provided [`StoragesKey`],
+    /// in the order of its storage slots.
+    fn get_storages(&mut self, key: StoragesKey) -> Result<Vec<U256>, MemorizerError>;
+
+    /// Retrieves the last change of the storage slot of the provided [`StorageChangeKey`] within
+    /// its block range.
+    ///
+    /// Returns `None` if the slot holds the same value at every block of the range.
+    fn get_storage_change(
+        &mut self,
+        key: StorageChangeKey,
+    ) -> Result<Option<StorageChange>, MemorizerError>;
+}
+
+/// A change of a storage slot between two consecutive blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageChange {
+    /// First block holding the new value.
+    pub block_number: u64,
+    /// Value held at the previous block.
+    pub previous_value: U256,
+    /// Value held at `block_number`, which is the value at the end of the range.
+    pub value: U256,
 }
 
 cfg_if! {
@@ -1,15 +1,19 @@
-use super::StorageMemorizer;
+use super::{StorageChange, StorageMemorizer};
 use crate::account::AccountProvider;
-use crate::memorizer::values::StorageMemorizerValue;
-use crate::memorizer::{keys::StorageKey, Memorizer};
+use crate::memorizer::values::{StorageChangeMemorizerValue, StorageMemorizerValue};
+use crate::memorizer::{
+    keys::{StorageChangeKey, StorageKey, StoragesKey},
+    Memorizer,
+};
 use crate::memorizer::{
     AccountKey, AccountMemorizerValue, HeaderKey, HeaderMemorizer, MemorizerError, MemorizerKey,
     MemorizerValue,
 };
+use crate::mmr::MmrHasher;
 use alloy_primitives::U256;
 use tokio::runtime::Runtime;
 
-impl StorageMemorizer for Memorizer {
+impl<H: MmrHasher> StorageMemorizer for Memorizer<H> {
     fn get_storage(&mut self, key: StorageKey) -> Result<U256, MemorizerError> {
         let header_key = HeaderKey {
             block_number: key.block_number,
@@ -59,4 +63,103 @@ impl StorageMemorizer for Memorizer {
 
         Ok(storage_value)
     }
+
+    fn get_storages(&mut self, key: StoragesKey) -> Result<Vec<U256>, MemorizerError> {
+        if key.storage_slots.is_empty() {
+            return Ok(Vec::new());
+        }
+        let header_key = HeaderKey {
+            block_number: key.block_number,
+            chain_id: key.chain_id,
+        };
+        let _ = self.get_header(header_key)?;
+
+        let rt = Runtime::new()?;
+        let rpc_url = self
+            .chain_map
+            .get(&key.chain_id)
+            .ok_or(MemorizerError::MissingRpcUrl(key.chain_id))?
+            .to_owned();
+        let (account, account_proof, storages) = rt.block_on(async {
+            let client: AccountProvider = AccountProvider::new(rpc_url);
+            client
+                .get_storages(key.address, key.block_number, &key.storage_slots)
+                .await
+                .map_err(MemorizerError::TransportError)
+        })?;
+
+        let account_key: MemorizerKey = AccountKey {
+            block_number: key.block_number,
+            chain_id: key.chain_id,
+            address: key.address,
+        }
+        .into();
+
+        self.map.entry(account_key).or_insert((
+            MemorizerValue::Account(AccountMemorizerValue {
+                account,
+                proof: account_proof,
+            }),
+            false,
+        ));
+
+        let mut values = Vec::with_capacity(storages.len());
+        for (storage_key, (proof, value)) in key.storage_keys().zip(storages) {
+            self.map.insert(
+                storage_key.into(),
+                (
+                    MemorizerValue::Storage(StorageMemorizerValue { value, proof }),
+                    false,
+                ),
+            );
+            values.push(value);
+        }
+
+        Ok(values)
+    }
+
+    fn get_storage_change(
+        &mut self,
+        key: StorageChangeKey,
+    ) -> Result<Option<StorageChange>, MemorizerError> {
+        if key.from_block > key.to_block {
+            return Err(MemorizerError::InvalidBlockRange(
+                key.from_block,
+                key.to_block,
+            ));
+        }
+        let storage_key = |block_number| StorageKey {
+            chain_id: key.chain_id,
+            block_number,
+            address: key.address,
+            storage_slot: key.storage_slot,
+        };
+
+        // Walk back from the end of the range: every block up to the change point is memorized,
+        // as the zkVM must see that the slot did not change again.
+        let value = self.get_storage(storage_key(key.to_block))?;
+        let mut change = None;
+        for block_number in (key.from_block + 1..=key.to_block).rev() {
+            let previous_value = self.get_storage(storage_key(block_number - 1))?;
+            if previous_value != value {
+                change = Some(StorageChange {
+                    block_number,
+                    previous_value,
+                    value,
+                });
+                break;
+            }
+        }
+
+        self.map.insert(
+            key.into(),
+            (
+                MemorizerValue::StorageChange(StorageChangeMemorizerValue {
+                    block_number: change.map(|change| change.block_number),
+                }),
+                false,
+            ),
+        );
+        Ok(change)
+    }
 }
@@ -1,13 +1,14 @@
-use super::StorageMemorizer;
+use super::{StorageChange, StorageMemorizer};
 use crate::memorizer::{
-    keys::{AccountKey, HeaderKey, MemorizerKey, StorageKey},
+    keys::{AccountKey, HeaderKey, MemorizerKey, StorageChangeKey, StorageKey, StoragesKey},
     values::MemorizerValue,
     AccountMemorizer, HeaderMemorizer, Memorizer, MemorizerError,
 };
+use crate::mmr::MmrHasher;
 use crate::mpt::Mpt;
 use alloy_primitives::U256;
 
-impl StorageMemorizer for Memorizer {
+impl<H: MmrHasher> StorageMemorizer for Memorizer<H> {
     fn get_storage(&mut self, key: StorageKey) -> Result<U256, MemorizerError> {
         // 1. Header
         let header_key = HeaderKey {
@@ -50,4 +51,71 @@ impl StorageMemorizer for Memorizer {
             Err(MemorizerError::MissingStorage)
         }
     }
+
+    fn get_storages(&mut self, key: StoragesKey) -> Result<Vec<U256>, MemorizerError> {
+        key.storage_keys()
+            .map(|storage_key| self.get_storage(storage_key))
+            .collect()
+    }
+
+    fn get_storage_change(
+        &mut self,
+        key: StorageChangeKey,
+    ) -> Result<Option<StorageChange>, MemorizerError> {
+        if key.from_block > key.to_block {
+            return Err(MemorizerError::InvalidBlockRange(
+                key.from_block,
+                key.to_block,
+            ));
+        }
+        let storage_key = |block_number| StorageKey {
+            chain_id: key.chain_id,
+            block_number,
+            address: key.address,
+            storage_slot: key.storage_slot,
+        };
+
+        let change_key: MemorizerKey = key.clone().into();
+        let block_number = match self.map.get(&change_key) {
+            Some((MemorizerValue::StorageChange(change_value), _)) => change_value.block_number,
+            _ => return Err(MemorizerError::MissingStorage),
+        };
+
+        // The slot holds its end value from the claimed change point, or from the start of the
+        // range if it did not change, through the end of the range.
+        let value = self.get_storage(storage_key(key.to_block))?;
+        let unchanged_from = match block_number {
+            Some(block_number) if key.from_block < block_number && block_number <= key.to_block => {
+                block_number
+            }
+            Some(_) => return Err(MemorizerError::InvalidStorageChange),
+            None => key.from_block,
+        };
+        for block_number in unchanged_from..key.to_block {
+            if self.get_storage(storage_key(block_number))? != value {
+                return Err(MemorizerError::InvalidStorageChange);
+            }
+        }
+
+        // The block before the change point must hold another value.
+        let change = match block_number {
+            Some(block_number) => {
+                let previous_value = self.get_storage(storage_key(block_number - 1))?;
+                if previous_value == value {
+                    return Err(MemorizerError::InvalidStorageChange);
+                }
+                Some(StorageChange {
+                    block_number,
+                    previous_value,
+                    value,
+                })
+            }
+            None => None,
+        };
+
+        if let Some((_, is_verified)) = self.map.get_mut(&change_key) {
+            *is_verified = true;
+        }
+        Ok(change)
+    }
 }
@@ -1,6 +1,15 @@
-use super::{keys::TransactionKey, MemorizerError};
+use super::{
+    keys::{TransactionCountKey, TransactionHashKey, TransactionKey},
+    MemorizerError,
+};
+use crate::chain::ChainId;
 use alloy_consensus::TxEnvelope;
 use cfg_if::cfg_if;
+#[cfg(any(target_os = "zkvm", test))]
+use {
+    super::values::TransactionMemorizerValue, crate::mpt::Mpt, alloy_eips::eip2718::Encodable2718,
+    alloy_primitives::B256, alloy_rlp::Decodable,
+};
 
 /// Defines a trait for managing and retrieving transactions from the memorizer.
 ///
@@ -16,9 +25,113 @@ use cfg_if::cfg_if;
 ///   it undergoes verification.
 /// - In both cases, if the `is_verified` flag is `true`, the memorized header or transaction
 ///   is read directly without additional verification.
+///
+/// ### Lookup by Hash
+/// In online mode, the transaction hash is resolved to its block number and index with
+/// `eth_getTransactionByHash`, and the resolved position is memorized alongside the transaction.
+/// In zkVM mode, the transaction at the memorized position is verified as usual and must hash
+/// to the requested transaction hash.
+///
+/// ### Transaction Count
+/// The number of transactions `n` in a block is proven by the inclusion of the transaction at
+/// index `n - 1` and the exclusion of index `n` from the header's `transactions_root`.
+/// Iterating a block with [`TransactionMemorizer::get_block_transactions`] relies on this count,
+/// so no transaction can be skipped.
 pub trait TransactionMemorizer {
     /// Retrieves a transaction based on the provided [`TransactionKey`].
     fn get_transaction(&mut self, key: TransactionKey) -> Result<TxEnvelope, MemorizerError>;
+
+    /// Retrieves a transaction based on the provided [`TransactionHashKey`].
+    fn get_transaction_by_hash(
+        &mut self,
+        key: TransactionHashKey,
+    ) -> Result<TxEnvelope, MemorizerError>;
+
+    /// Retrieves the number of transactions in a block based on the provided [`TransactionCountKey`].
+    fn get_transaction_count(&mut self, key: TransactionCountKey) -> Result<u64, MemorizerError>;
+
+    /// Returns an iterator over every transaction of a block, in index order.
+    fn get_block_transactions(
+        &mut self,
+        key: TransactionCountKey,
+    ) -> Result<BlockTransactions<'_, Self>, MemorizerError>
+    where
+        Self: Sized,
+    {
+        let chain_id = key.chain_id;
+        let block_number = key.block_number;
+        let count = self.get_transaction_count(key)?;
+        Ok(BlockTransactions {
+            memorizer: self,
+            chain_id,
+            block_number,
+            next_index: 0,
+            count,
+        })
+    }
+}
+
+/// Iterator over all transactions of a block, created by [`TransactionMemorizer::get_block_transactions`].
+#[derive(Debug)]
+pub struct BlockTransactions<'a, M> {
+    memorizer: &'a mut M,
+    chain_id: ChainId,
+    block_number: u64,
+    next_index: u64,
+    count: u64,
+}
+
+impl<M: TransactionMemorizer> BlockTransactions<'_, M> {
+    /// Returns the proven number of transactions in the block.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl<M: TransactionMemorizer> Iterator for BlockTransactions<'_, M> {
+    type Item = Result<TxEnvelope, MemorizerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_index >= self.count {
+            return None;
+        }
+        let key = TransactionKey {
+            chain_id: self.chain_id,
+            block_number: self.block_number,
+            transaction_index: self.next_index,
+        };
+        self.next_index += 1;
+        Some(self.memorizer.get_transaction(key))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.count - self.next_index) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+/// Verifies a memorized transaction against the transactions root at the index of its key.
+///
+/// The index memorized with the transaction is supplied by the prover, so it must match the
+/// requested index: otherwise a transaction proven at another index could be served in place
+/// of the requested one.
+#[cfg(any(target_os = "zkvm", test))]
+fn verify_transaction_value(
+    tx_root: B256,
+    transaction_index: u64,
+    tx_value: &TransactionMemorizerValue,
+) -> Result<TxEnvelope, MemorizerError> {
+    if tx_value.tx_index != transaction_index {
+        return Err(MemorizerError::MissingTransaction);
+    }
+    let tx = TxEnvelope::decode(&mut tx_value.transaction_encoded.as_ref())?;
+    let mpt = Mpt { root: tx_root };
+    mpt.verify_transaction(
+        transaction_index,
+        &tx.encoded_2718(),
+        tx_value.proof.clone(),
+    )?;
+    Ok(tx)
 }
 
 cfg_if! {
@@ -28,3 +141,42 @@ cfg_if! {
         mod online;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpt::ordered_trie_with_proof;
+    use alloy_consensus::{SignableTransaction, TxLegacy};
+    use alloy_primitives::{Bytes, Signature};
+
+    #[test]
+    fn test_verify_transaction_value_index() {
+        let encoded: Vec<Bytes> = (0..3)
+            .map(|nonce| {
+                let tx: TxEnvelope = TxLegacy {
+                    nonce,
+                    gas_limit: 21_000,
+                    ..Default::default()
+                }
+                .into_signed(Signature::test_signature())
+                .into();
+                tx.encoded_2718().into()
+            })
+            .collect();
+        let (tx_root, proof) = ordered_trie_with_proof(&encoded, 1);
+        let tx_value = TransactionMemorizerValue {
+            transaction_encoded: encoded[1].clone(),
+            tx_index: 1,
+            proof,
+        };
+
+        let tx = verify_transaction_value(tx_root, 1, &tx_value).unwrap();
+        assert_eq!(Bytes::from(tx.encoded_2718()), encoded[1]);
+
+        // Transaction 1, proven at its own index, cannot be served as transaction 0
+        assert!(matches!(
+            verify_transaction_value(tx_root, 0, &tx_value),
+            Err(MemorizerError::MissingTransaction)
+        ));
+    }
+}
@@ -1,12 +1,21 @@
 use super::TransactionMemorizer;
-use crate::memorizer::{values::TransactionMemorizerValue, Memorizer, MemorizerError};
-use crate::memorizer::{HeaderKey, HeaderMemorizer};
-use crate::transaction::{TransactionClient, TransactionResponse};
+use crate::memorizer::{
+    values::{
+        MemorizerValue, TransactionCountMemorizerValue, TransactionHashMemorizerValue,
+        TransactionMemorizerValue,
+    },
+    Memorizer, MemorizerError, MemorizerKey,
+};
+use crate::memorizer::{
+    HeaderKey, HeaderMemorizer, TransactionCountKey, TransactionHashKey, TransactionKey,
+};
+use crate::mmr::MmrHasher;
+use crate::transaction::{TransactionClient, TransactionCountResponse, TransactionResponse};
 use alloy_consensus::TxEnvelope;
 use alloy_rlp::Encodable;
 use tokio::runtime::Runtime;
 
-impl TransactionMemorizer for Memorizer {
+impl<H: MmrHasher> TransactionMemorizer for Memorizer<H> {
     fn get_transaction(
         &mut self,
         key: crate::memorizer::keys::TransactionKey,
@@ -49,4 +58,110 @@ impl TransactionMemorizer for Memorizer {
 
         Ok(tx)
     }
+
+    fn get_transaction_by_hash(
+        &mut self,
+        key: TransactionHashKey,
+    ) -> Result<TxEnvelope, MemorizerError> {
+        let tx_key = self.locate_transaction(key)?;
+        self.get_transaction(tx_key)
+    }
+
+    fn get_transaction_count(&mut self, key: TransactionCountKey) -> Result<u64, MemorizerError> {
+        let header_key = HeaderKey {
+            block_number: key.block_number,
+            chain_id: key.chain_id,
+        };
+        let _ = self.get_header(header_key)?;
+
+        let rt = Runtime::new()?;
+        let rpc_url = self
+            .chain_map
+            .get(&key.chain_id)
+            .ok_or(MemorizerError::MissingRpcUrl(key.chain_id))?
+            .to_owned();
+        let response: TransactionCountResponse = rt.block_on(async {
+            let client = TransactionClient::default();
+            client
+                .get_transaction_count(rpc_url, key.block_number)
+                .await
+                .map_err(MemorizerError::EthTrieError)
+        })?;
+
+        // The last transaction is memorized so the zkVM can prove its inclusion.
+        if response.count > 0 {
+            let _ = self.get_transaction(TransactionKey {
+                chain_id: key.chain_id,
+                block_number: key.block_number,
+                transaction_index: response.count - 1,
+            })?;
+        }
+
+        self.map.insert(
+            key.into(),
+            (
+                MemorizerValue::TransactionCount(TransactionCountMemorizerValue {
+                    count: response.count,
+                    exclusion_proof: response.exclusion_proof,
+                }),
+                false,
+            ),
+        );
+
+        Ok(response.count)
+    }
+}
+
+impl<H: MmrHasher> Memorizer<H> {
+    /// Resolves a transaction hash to the [`TransactionKey`] of the transaction,
+    /// memorizing the resolved position for the zkVM run.
+    pub(crate) fn locate_transaction(
+        &mut self,
+        key: TransactionHashKey,
+    ) -> Result<TransactionKey, MemorizerError> {
+        let chain_id = key.chain_id;
+        let tx_hash = key.transaction_hash;
+        let hash_key: MemorizerKey = key.into();
+
+        if let Some((MemorizerValue::TransactionHash(location), _)) = self.map.get(&hash_key) {
+            return Ok(TransactionKey {
+                chain_id,
+                block_number: location.block_number,
+                transaction_index: location.tx_index,
+            });
+        }
+
+        let rt = Runtime::new()?;
+        let rpc_url = self
+            .chain_map
+            .get(&chain_id)
+            .ok_or(MemorizerError::MissingRpcUrl(chain_id))?
+            .to_owned();
+        let (block_number, tx_index) = rt
+            .block_on(async {
+                let client = TransactionClient::default();
+                client
+                    .get_transaction_location(rpc_url, tx_hash)
+                    .await
+                    .map_err(MemorizerError::TransportError)
+            })?
+            .ok_or(MemorizerError::MissingTransaction)?;
+
+        self.map.insert(
+            hash_key,
+            (
+                MemorizerValue::TransactionHash(TransactionHashMemorizerValue {
+                    block_number,
+                    tx_index,
+                }),
+                false,
+            ),
+        );
+
+        Ok(TransactionKey {
+            chain_id,
+            block_number,
+            transaction_index: tx_index,
+        })
+    }
 }
@@ -1,13 +1,14 @@
-use super::TransactionMemorizer;
+use super::{verify_transaction_value, TransactionMemorizer};
 use crate::memorizer::{
-    keys::HeaderKey, keys::MemorizerKey, keys::TransactionKey, values::MemorizerValue,
-    HeaderMemorizer, Memorizer, MemorizerError,
+    keys::HeaderKey, keys::MemorizerKey, keys::TransactionCountKey, keys::TransactionHashKey,
+    keys::TransactionKey, values::MemorizerValue, HeaderMemorizer, Memorizer, MemorizerError,
 };
+use crate::mmr::MmrHasher;
 use crate::mpt::Mpt;
 use alloy_consensus::TxEnvelope;
 use alloy_rlp::Decodable;
 
-impl TransactionMemorizer for Memorizer {
+impl<H: MmrHasher> TransactionMemorizer for Memorizer<H> {
     fn get_transaction(&mut self, key: TransactionKey) -> Result<TxEnvelope, MemorizerError> {
         // 1. Header
         let header_key = HeaderKey {
@@ -18,6 +19,7 @@ impl TransactionMemorizer for Memorizer {
 
         // 2. Transaction
         let tx_root = header.transactions_root;
+        let transaction_index = key.transaction_index;
         let tx_key: MemorizerKey = key.into();
 
         if let Some((MemorizerValue::Transaction(tx_value), is_verified)) =
@@ -25,19 +27,103 @@ impl TransactionMemorizer for Memorizer {
         {
             if *is_verified {
                 println!("Transaction MPT already verified");
-                let tx_encoded = tx_value.transaction_encoded.clone();
-                Ok(TxEnvelope::decode(&mut tx_encoded.as_ref())?)
+                Ok(TxEnvelope::decode(
+                    &mut tx_value.transaction_encoded.as_ref(),
+                )?)
             } else {
-                let mpt = Mpt { root: tx_root };
                 println!("cycle-tracker-start: mpt (transaction)");
-                mpt.verify_transaction(tx_value.tx_index, tx_value.proof.clone())?;
+                let tx = verify_transaction_value(tx_root, transaction_index, tx_value)?;
                 println!("cycle-tracker-end: mpt (transaction)");
                 *is_verified = true;
-                let tx_encoded = tx_value.transaction_encoded.clone();
-                Ok(TxEnvelope::decode(&mut tx_encoded.as_ref())?)
+                Ok(tx)
             }
         } else {
             Err(MemorizerError::MissingTransaction)
         }
     }
+
+    fn get_transaction_by_hash(
+        &mut self,
+        key: TransactionHashKey,
+    ) -> Result<TxEnvelope, MemorizerError> {
+        let tx_hash = key.transaction_hash;
+        let tx_key = self.locate_transaction(key)?;
+        let tx = self.get_transaction(tx_key)?;
+
+        if *tx.tx_hash() == tx_hash {
+            Ok(tx)
+        } else {
+            Err(MemorizerError::TransactionHashMismatch)
+        }
+    }
+
+    fn get_transaction_count(&mut self, key: TransactionCountKey) -> Result<u64, MemorizerError> {
+        // 1. Header
+        let header_key = HeaderKey {
+            block_number: key.block_number,
+            chain_id: key.chain_id,
+        };
+        let header = self.get_header(header_key)?;
+
+        // 2. Transaction count
+        let tx_root = header.transactions_root;
+        let chain_id = key.chain_id;
+        let block_number = key.block_number;
+        let count_key: MemorizerKey = key.into();
+
+        let (count, exclusion_proof) = match self.map.get(&count_key) {
+            Some((MemorizerValue::TransactionCount(count_value), true)) => {
+                println!("Transaction count already verified");
+                return Ok(count_value.count);
+            }
+            Some((MemorizerValue::TransactionCount(count_value), false)) => {
+                (count_value.count, count_value.exclusion_proof.clone())
+            }
+            _ => return Err(MemorizerError::MissingTransaction),
+        };
+
+        // The last transaction must be included ...
+        if count > 0 {
+            let _ = self.get_transaction(TransactionKey {
+                chain_id,
+                block_number,
+                transaction_index: count - 1,
+            })?;
+        }
+
+        // ... and the next index must be absent.
+        let mpt = Mpt { root: tx_root };
+        println!("cycle-tracker-start: mpt (transaction exclusion)");
+        mpt.verify_transaction_exclusion(count, exclusion_proof)?;
+        println!("cycle-tracker-end: mpt (transaction exclusion)");
+
+        if let Some((_, is_verified)) = self.map.get_mut(&count_key) {
+            *is_verified = true;
+        }
+        Ok(count)
+    }
+}
+
+impl<H: MmrHasher> Memorizer<H> {
+    /// Reads the memorized position of a transaction hash.
+    ///
+    /// The position itself is not trusted: callers must check that the transaction
+    /// proven at this position hashes to the requested hash.
+    pub(crate) fn locate_transaction(
+        &self,
+        key: TransactionHashKey,
+    ) -> Result<TransactionKey, MemorizerError> {
+        let chain_id = key.chain_id;
+        let hash_key: MemorizerKey = key.into();
+
+        if let Some((MemorizerValue::TransactionHash(location), _)) = self.map.get(&hash_key) {
+            Ok(TransactionKey {
+                chain_id,
+                block_number: location.block_number,
+                transaction_index: location.tx_index,
+            })
+        } else {
+            Err(MemorizerError::MissingTransaction)
+        }
+    }
 }
@@ -0,0 +1,145 @@
+use super::{
+    keys::{HeaderKey, ReceiptKey, TransactionKey},
+    HeaderMemorizer, MemorizerError, ReceiptMemorizer, TransactionMemorizer,
+};
+use alloy_consensus::{Transaction, TxEnvelope};
+use alloy_primitives::Address;
+use cfg_if::cfg_if;
+
+/// A verified transaction joined with its receipt and the block base fee.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionFull {
+    /// The decoded transaction.
+    pub tx: TxEnvelope,
+    /// Sender recovered from the transaction signature.
+    pub sender: Address,
+    /// Whether the transaction succeeded.
+    pub status: bool,
+    /// Gas used by the transaction alone.
+    pub gas_used: u128,
+    /// Price per unit of gas actually paid, including the priority fee.
+    pub effective_gas_price: u128,
+    /// Execution fee paid, `gas_used * effective_gas_price`. Blob gas is not included.
+    pub fee: u128,
+}
+
+/// Defines a trait for retrieving a transaction together with its receipt and derived fields.
+///
+/// Every part is read through [`TransactionMemorizer`], [`ReceiptMemorizer`] and
+/// [`HeaderMemorizer`], so the result is verified in zkVM mode.
+///
+/// ### Gas Used
+/// Receipts only commit to the cumulative gas used in the block, so the gas used by a
+/// transaction is the difference with the cumulative gas of the previous receipt.
+///
+/// ### Sender
+/// Recovering the sender is costly, so it is memorized once recovered. In zkVM mode, only
+/// senders recovered inside the zkVM are trusted: senders memorized in online mode are
+/// recovered again.
+pub trait TransactionFullMemorizer {
+    /// Retrieves the sender of a transaction based on the provided [`TransactionKey`].
+    fn get_sender(&mut self, key: TransactionKey) -> Result<Address, MemorizerError>;
+
+    /// Retrieves a transaction with its sender, status, gas used and fee based on the provided [`TransactionKey`].
+    fn get_transaction_full(
+        &mut self,
+        key: TransactionKey,
+    ) -> Result<TransactionFull, MemorizerError>;
+}
+
+cfg_if! {
+    if #[cfg(target_os = "zkvm")] {
+        mod zkvm;
+    } else {
+        mod online;
+    }
+}
+
+/// Joins the transaction of `key` with its receipt and the base fee of its block.
+fn transaction_full<M>(
+    memorizer: &mut M,
+    key: TransactionKey,
+) -> Result<TransactionFull, MemorizerError>
+where
+    M: TransactionFullMemorizer + TransactionMemorizer + ReceiptMemorizer + HeaderMemorizer,
+{
+    let chain_id = key.chain_id;
+    let block_number = key.block_number;
+    let transaction_index = key.transaction_index;
+
+    let tx = memorizer.get_transaction(TransactionKey {
+        chain_id,
+        block_number,
+        transaction_index,
+    })?;
+    let sender = memorizer.get_sender(key)?;
+
+    let receipt = memorizer.get_receipt(ReceiptKey {
+        chain_id,
+        block_number,
+        transaction_index,
+    })?;
+    let cumulative_gas_used = u128::from(receipt.cumulative_gas_used());
+    let gas_used = if transaction_index == 0 {
+        cumulative_gas_used
+    } else {
+        let previous = memorizer.get_receipt(ReceiptKey {
+            chain_id,
+            block_number,
+            transaction_index: transaction_index - 1,
+        })?;
+        // The cumulative gas of a block never decreases from one receipt to the next.
+        cumulative_gas_used
+            .checked_sub(u128::from(previous.cumulative_gas_used()))
+            .ok_or(MemorizerError::MissingReceipt)?
+    };
+
+    let header = memorizer.get_header(HeaderKey {
+        chain_id,
+        block_number,
+    })?;
+    let effective_gas_price = effective_gas_price(&tx, header.base_fee_per_gas.map(u128::from));
+
+    Ok(TransactionFull {
+        tx,
+        sender,
+        status: receipt.status(),
+        gas_used,
+        effective_gas_price,
+        fee: gas_used * effective_gas_price,
+    })
+}
+
+/// Computes the price per unit of gas paid by `tx` in a block with the given base fee.
+fn effective_gas_price(tx: &TxEnvelope, base_fee: Option<u128>) -> u128 {
+    match (tx.gas_price(), base_fee) {
+        (Some(gas_price), _) => gas_price,
+        (None, None) => tx.max_fee_per_gas(),
+        (None, Some(base_fee)) => {
+            let priority_fee = tx.max_priority_fee_per_gas().unwrap_or_default();
+            tx.max_fee_per_gas()
+                .min(base_fee.saturating_add(priority_fee))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+    use alloy_rlp::Decodable;
+
+    #[test]
+    fn test_effective_gas_price() {
+        // EIP-1559 transaction with a max priority fee of 1 gwei and a max fee of 11.249 gwei.
+        let raw_tx = hex::decode("02f86f0102843b9aca0085029e7822d68298f094d9e1459a7a482635700cbc20bbaf52d495ab9c9680841b55ba3ac080a0c199674fcb29f353693dd779c017823b954b3c69dffa3cd6b2a6ff7888798039a028ca912de909e7e6cdef9cdcaf24c54dd8c1032946dfa1d85c206b32a9064fe8").unwrap();
+        let tx = TxEnvelope::decode(&mut raw_tx.as_slice()).unwrap();
+
+        assert_eq!(effective_gas_price(&tx, Some(5_000_000_000)), 6_000_000_000);
+        assert_eq!(
+            effective_gas_price(&tx, Some(11_000_000_000)),
+            11_248_607_958
+        );
+        assert_eq!(effective_gas_price(&tx, None), 11_248_607_958);
+    }
+}
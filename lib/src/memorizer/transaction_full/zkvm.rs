@@ -0,0 +1,45 @@
+use super::{transaction_full, TransactionFull, TransactionFullMemorizer};
+use crate::memorizer::{
+    keys::{MemorizerKey, SenderKey, TransactionKey},
+    values::{MemorizerValue, SenderMemorizerValue},
+    Memorizer, MemorizerError, TransactionMemorizer,
+};
+use crate::mmr::MmrHasher;
+use alloy_primitives::Address;
+
+impl<H: MmrHasher> TransactionFullMemorizer for Memorizer<H> {
+    fn get_sender(&mut self, key: TransactionKey) -> Result<Address, MemorizerError> {
+        let sender_key: MemorizerKey = SenderKey {
+            chain_id: key.chain_id,
+            block_number: key.block_number,
+            transaction_index: key.transaction_index,
+        }
+        .into();
+
+        if let Some((MemorizerValue::Sender(sender_value), true)) = self.map.get(&sender_key) {
+            println!("Sender already recovered");
+            return Ok(sender_value.sender);
+        }
+
+        let tx = self.get_transaction(key)?;
+        println!("cycle-tracker-start: recover signer");
+        let sender = tx.recover_signer()?;
+        println!("cycle-tracker-end: recover signer");
+
+        self.map.insert(
+            sender_key,
+            (
+                MemorizerValue::Sender(SenderMemorizerValue { sender }),
+                true,
+            ),
+        );
+        Ok(sender)
+    }
+
+    fn get_transaction_full(
+        &mut self,
+        key: TransactionKey,
+    ) -> Result<TransactionFull, MemorizerError> {
+        transaction_full(self, key)
+    }
+}
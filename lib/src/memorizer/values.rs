@@ -1,7 +1,8 @@
 use crate::memorizer::cl_header::BeaconHeader;
+use crate::mmr::MmrMultiProof;
 use alloy_consensus::serde_bincode_compat;
 use alloy_consensus::{Account, Header};
-use alloy_primitives::{Bytes, B256, U256};
+use alloy_primitives::{Address, Bytes, B256, U256};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -22,6 +23,20 @@ pub struct HeaderMemorizerValue {
     pub proof: Vec<B256>,
 }
 
+/// Stores a multi-proof covering the headers of many blocks.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HeadersMemorizerValue {
+    /// MMR multi-proof of the headers, each memorized as a [`HeaderMemorizerValue`].
+    pub multi_proof: MmrMultiProof,
+}
+
+/// Stores the headers linking a block to an anchor block in the MMR.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChainedHeaderMemorizerValue {
+    /// RLP-encoded headers from the lower to the higher of the two blocks, without the anchor.
+    pub rlp_headers: Vec<Bytes>,
+}
+
 /// Stores an account with associated proof data.
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AccountMemorizerValue {
@@ -31,6 +46,13 @@ pub struct AccountMemorizerValue {
     pub proof: Vec<Bytes>,
 }
 
+/// Stores the bytecode of an account.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CodeMemorizerValue {
+    /// Bytecode of the account.
+    pub code: Bytes,
+}
+
 /// Represents a memorized Beacon chain header.
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BeaconHeaderMemorizerValue {
@@ -58,6 +80,16 @@ pub struct TransactionMemorizerValue {
     pub proof: Vec<Bytes>,
 }
 
+/// Represents the memorized number of transactions in a block with the proof that
+/// no transaction exists past the last one.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransactionCountMemorizerValue {
+    /// Number of transactions in the block.
+    pub count: u64,
+    /// Exclusion proof for the transaction index equal to `count`.
+    pub exclusion_proof: Vec<Bytes>,
+}
+
 /// Represents a memorized transaction receipt with associated proof.
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReceiptMemorizerValue {
@@ -69,19 +101,89 @@ pub struct ReceiptMemorizerValue {
     pub proof: Vec<Bytes>,
 }
 
+/// Represents a memorized withdrawal with associated proof.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WithdrawalMemorizerValue {
+    /// RLP-encoded withdrawal data.
+    pub withdrawal_encoded: Bytes,
+    /// Withdrawal index within the block.
+    pub withdrawal_index: u64,
+    /// Proof elements for withdrawal verification.
+    pub proof: Vec<Bytes>,
+}
+
+/// Points a transaction hash to the position of the transaction within its block.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransactionHashMemorizerValue {
+    /// Block number containing the transaction.
+    pub block_number: u64,
+    /// Transaction index within the block.
+    pub tx_index: u64,
+}
+
+/// Sender recovered from the signature of a verified transaction.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SenderMemorizerValue {
+    /// Address of the transaction sender.
+    pub sender: Address,
+}
+
+/// Points a timestamp to the block whose header covers it.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimestampMemorizerValue {
+    /// Number of the last block produced at or before the timestamp.
+    pub block_number: u64,
+}
+
+/// Points a storage slot to the block at which it changed within a range of blocks.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StorageChangeMemorizerValue {
+    /// Block of the last change in the range, or `None` if the slot holds the same value at every block of the range.
+    pub block_number: Option<u64>,
+}
+
+/// Points an account to the block at which it was created, with the proof of its absence before.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AccountCreationMemorizerValue {
+    /// First block at which the account exists.
+    pub block_number: u64,
+    /// Proof elements for the absence of the account at the previous block.
+    pub exclusion_proof: Vec<Bytes>,
+}
+
 /// Enum encapsulating different types of data that can be memorized.
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub enum MemorizerValue {
     /// header and proof.
     Header(HeaderMemorizerValue),
+    /// Multi-proof of many headers.
+    Headers(HeadersMemorizerValue),
+    /// Headers linking a block to an anchor block.
+    ChainedHeader(ChainedHeaderMemorizerValue),
     /// account and proof.
     Account(AccountMemorizerValue),
     /// Storage value and proof.
     Storage(StorageMemorizerValue),
+    /// Account bytecode.
+    Code(CodeMemorizerValue),
     /// Transaction and proof.
     Transaction(TransactionMemorizerValue),
+    /// Transaction count and exclusion proof.
+    TransactionCount(TransactionCountMemorizerValue),
     /// Transaction receipt and proof.
     Receipt(ReceiptMemorizerValue),
     /// Beacon header.
     BeaconHeader(BeaconHeaderMemorizerValue),
+    /// Withdrawal and proof.
+    Withdrawal(WithdrawalMemorizerValue),
+    /// Position of a transaction looked up by hash.
+    TransactionHash(TransactionHashMemorizerValue),
+    /// Recovered transaction sender.
+    Sender(SenderMemorizerValue),
+    /// Block number covering a timestamp.
+    Timestamp(TimestampMemorizerValue),
+    /// Change point of a storage slot.
+    StorageChange(StorageChangeMemorizerValue),
+    /// Creation block of an account and exclusion proof.
+    AccountCreation(AccountCreationMemorizerValue),
 }
@@ -0,0 +1,97 @@
+use super::{keys::WithdrawalKey, MemorizerError};
+use alloy_eips::eip4895::Withdrawal;
+use cfg_if::cfg_if;
+#[cfg(any(target_os = "zkvm", test))]
+use {
+    super::values::WithdrawalMemorizerValue, crate::mpt::Mpt, alloy_primitives::B256,
+    alloy_rlp::Decodable,
+};
+
+/// Defines a trait for managing and retrieving validator withdrawals from the memorizer.
+///
+/// ### Online Mode
+/// In online mode, if a requested withdrawal's dependent header is missing from the memorizer,
+/// it is fetched automatically. The withdrawals trie is then rebuilt from the block body to
+/// produce the proof, and the requested withdrawal is returned.
+///
+/// ### zkVM Mode
+/// In zkVM (Zero-Knowledge Virtual Machine) mode:
+/// - If the dependent header exists but its `is_verified` flag is `false`, it is verified first.
+/// - The withdrawal is then verified against the header's `withdrawals_root` if its `is_verified` flag is `false`.
+/// - In both cases, if the `is_verified` flag is `true`, the memorized header or withdrawal
+///   is read directly without additional verification.
+pub trait WithdrawalMemorizer {
+    /// Retrieves a withdrawal based on the provided [`WithdrawalKey`].
+    fn get_withdrawal(&mut self, key: WithdrawalKey) -> Result<Withdrawal, MemorizerError>;
+}
+
+/// Verifies a memorized withdrawal against the withdrawals root at the index of its key.
+///
+/// The index memorized with the withdrawal is supplied by the prover, so it must match the
+/// requested index: otherwise a withdrawal proven at another index could be served in place
+/// of the requested one.
+#[cfg(any(target_os = "zkvm", test))]
+fn verify_withdrawal_value(
+    withdrawals_root: B256,
+    withdrawal_index: u64,
+    withdrawal_value: &WithdrawalMemorizerValue,
+) -> Result<Withdrawal, MemorizerError> {
+    if withdrawal_value.withdrawal_index != withdrawal_index {
+        return Err(MemorizerError::MissingWithdrawal);
+    }
+    let withdrawal = Withdrawal::decode(&mut withdrawal_value.withdrawal_encoded.as_ref())?;
+    let mpt = Mpt {
+        root: withdrawals_root,
+    };
+    mpt.verify_withdrawal(
+        withdrawal_index,
+        &withdrawal_value.withdrawal_encoded,
+        withdrawal_value.proof.clone(),
+    )?;
+    Ok(withdrawal)
+}
+
+cfg_if! {
+    if #[cfg(target_os = "zkvm")] {
+        mod zkvm;
+    } else {
+        mod online;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpt::ordered_trie_with_proof;
+    use alloy_primitives::{Address, Bytes};
+
+    #[test]
+    fn test_verify_withdrawal_value_index() {
+        let encoded: Vec<Bytes> = (0..3)
+            .map(|index| {
+                let withdrawal = Withdrawal {
+                    index,
+                    validator_index: 100 + index,
+                    address: Address::with_last_byte(index as u8),
+                    amount: 1_000,
+                };
+                alloy_rlp::encode(withdrawal).into()
+            })
+            .collect();
+        let (withdrawals_root, proof) = ordered_trie_with_proof(&encoded, 1);
+        let withdrawal_value = WithdrawalMemorizerValue {
+            withdrawal_encoded: encoded[1].clone(),
+            withdrawal_index: 1,
+            proof,
+        };
+
+        let withdrawal = verify_withdrawal_value(withdrawals_root, 1, &withdrawal_value).unwrap();
+        assert_eq!(withdrawal.index, 1);
+
+        // Withdrawal 1, proven at its own index, cannot be served as withdrawal 0
+        assert!(matches!(
+            verify_withdrawal_value(withdrawals_root, 0, &withdrawal_value),
+            Err(MemorizerError::MissingWithdrawal)
+        ));
+    }
+}
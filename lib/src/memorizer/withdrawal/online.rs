@@ -0,0 +1,49 @@
+use super::WithdrawalMemorizer;
+use crate::memorizer::values::{MemorizerValue, WithdrawalMemorizerValue};
+use crate::memorizer::{keys::WithdrawalKey, Memorizer};
+use crate::memorizer::{HeaderKey, HeaderMemorizer, MemorizerError};
+use crate::mmr::MmrHasher;
+use crate::withdrawal::{WithdrawalProvider, WithdrawalResponse};
+use alloy_eips::eip4895::Withdrawal;
+use tokio::runtime::Runtime;
+
+impl<H: MmrHasher> WithdrawalMemorizer for Memorizer<H> {
+    fn get_withdrawal(&mut self, key: WithdrawalKey) -> Result<Withdrawal, MemorizerError> {
+        let header_key = HeaderKey {
+            block_number: key.block_number,
+            chain_id: key.chain_id,
+        };
+        let _ = self.get_header(header_key)?;
+
+        let rt = Runtime::new()?;
+        let rpc_url = self
+            .chain_map
+            .get(&key.chain_id)
+            .ok_or(MemorizerError::MissingRpcUrl(key.chain_id))?
+            .to_owned();
+        let response: WithdrawalResponse = rt
+            .block_on(async {
+                let client = WithdrawalProvider::new(rpc_url);
+                client
+                    .get_withdrawal(key.block_number, key.withdrawal_index)
+                    .await
+                    .map_err(MemorizerError::TransportError)
+            })?
+            .ok_or(MemorizerError::MissingWithdrawal)?;
+
+        let withdrawal = response.withdrawal;
+        self.map.insert(
+            key.into(),
+            (
+                MemorizerValue::Withdrawal(WithdrawalMemorizerValue {
+                    withdrawal_encoded: alloy_rlp::encode(withdrawal).into(),
+                    withdrawal_index: response.withdrawal_index,
+                    proof: response.proof,
+                }),
+                false,
+            ),
+        );
+
+        Ok(withdrawal)
+    }
+}
@@ -0,0 +1,47 @@
+use super::{verify_withdrawal_value, WithdrawalMemorizer};
+use crate::memorizer::{
+    keys::{HeaderKey, MemorizerKey, WithdrawalKey},
+    values::MemorizerValue,
+    HeaderMemorizer, Memorizer, MemorizerError,
+};
+use crate::mmr::MmrHasher;
+use alloy_eips::eip4895::Withdrawal;
+use alloy_rlp::Decodable;
+
+impl<H: MmrHasher> WithdrawalMemorizer for Memorizer<H> {
+    fn get_withdrawal(&mut self, key: WithdrawalKey) -> Result<Withdrawal, MemorizerError> {
+        // 1. Header
+        let header_key = HeaderKey {
+            block_number: key.block_number,
+            chain_id: key.chain_id,
+        };
+        let header = self.get_header(header_key)?;
+
+        // 2. Withdrawal
+        let withdrawals_root = header
+            .withdrawals_root
+            .ok_or(MemorizerError::MissingWithdrawal)?;
+        let withdrawal_index = key.withdrawal_index;
+        let withdrawal_key: MemorizerKey = key.into();
+
+        if let Some((MemorizerValue::Withdrawal(withdrawal_value), is_verified)) =
+            self.map.get_mut(&withdrawal_key)
+        {
+            if *is_verified {
+                println!("Withdrawal MPT already verified");
+                Ok(Withdrawal::decode(
+                    &mut withdrawal_value.withdrawal_encoded.as_ref(),
+                )?)
+            } else {
+                println!("cycle-tracker-start: mpt (withdrawal)");
+                let withdrawal =
+                    verify_withdrawal_value(withdrawals_root, withdrawal_index, withdrawal_value)?;
+                println!("cycle-tracker-end: mpt (withdrawal)");
+                *is_verified = true;
+                Ok(withdrawal)
+            }
+        } else {
+            Err(MemorizerError::MissingWithdrawal)
+        }
+    }
+}
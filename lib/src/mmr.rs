@@ -1,19 +1,97 @@
 use alloy_primitives::keccak256;
 use alloy_primitives::{B256, U256};
 use serde::{Deserialize, Serialize};
+use starknet_crypto::{poseidon_hash, poseidon_hash_many, Felt};
+use std::{collections::HashMap, fmt::Debug, marker::PhantomData};
 use thiserror_no_std::Error;
 
+/// Hash function of an MMR, used for its leaves, for inclusion paths and for bagging the peaks.
+pub trait MmrHasher: Debug {
+    /// Name of the hash function, as expected by the Indexer.
+    const NAME: &'static str;
+
+    /// Hashes an element, such as an RLP-encoded block header, into its leaf.
+    fn hash_leaf(data: &[u8]) -> B256;
+
+    /// Hashes two nodes into their parent.
+    fn hash_pair(left: B256, right: B256) -> B256;
+}
+
+/// Keccak-256, as used by the Herodotus keccak accumulators.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct KeccakHasher;
+
+impl MmrHasher for KeccakHasher {
+    const NAME: &'static str = "keccak";
+
+    fn hash_leaf(data: &[u8]) -> B256 {
+        keccak256(data)
+    }
+
+    fn hash_pair(left: B256, right: B256) -> B256 {
+        keccak256([left, right].concat())
+    }
+}
+
+/// Poseidon over the Stark field, as used by the Herodotus poseidon accumulators.
+///
+/// Nodes are field elements, stored as 32 big-endian bytes. Leaves hash the element as a list
+/// of 64-bit little-endian words, the last one holding the remaining bytes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PoseidonHasher;
+
+impl MmrHasher for PoseidonHasher {
+    const NAME: &'static str = "poseidon";
+
+    fn hash_leaf(data: &[u8]) -> B256 {
+        let words: Vec<Felt> = data
+            .chunks(8)
+            .map(|chunk| {
+                let mut word = [0u8; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                Felt::from(u64::from_le_bytes(word))
+            })
+            .collect();
+        B256::from(poseidon_hash_many(&words).to_bytes_be())
+    }
+
+    fn hash_pair(left: B256, right: B256) -> B256 {
+        let hash = poseidon_hash(Felt::from_bytes_be(&left.0), Felt::from_bytes_be(&right.0));
+        B256::from(hash.to_bytes_be())
+    }
+}
+
 /// Represents metadata for an MMR (Merkle Mountain Range) structure,
 /// including the root hash, MMR size, and the peaks.
-#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
-pub struct MmrMeta {
+///
+/// The MMR is hashed with `H`, keccak by default.
+///
+/// The peaks only need to be checked against the root hash once per snapshot:
+/// after [`MmrMeta::validate_peaks`], element proofs skip bagging the peaks.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MmrMeta<H: MmrHasher = KeccakHasher> {
     pub mmr_id: U256,
     pub root_hash: B256,
     pub mmr_size: U256,
     peaks: Vec<B256>,
+    /// Whether the peaks were checked against the root hash and the size.
+    #[serde(skip)]
+    peaks_validated: bool,
+    #[serde(skip)]
+    hasher: PhantomData<H>,
 }
 
-impl MmrMeta {
+impl<H: MmrHasher> PartialEq for MmrMeta<H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.mmr_id == other.mmr_id
+            && self.root_hash == other.root_hash
+            && self.mmr_size == other.mmr_size
+            && self.peaks == other.peaks
+    }
+}
+
+impl<H: MmrHasher> MmrMeta<H> {
     /// Creates a new [`MmrMeta`] instance.
     ///
     /// # Arguments
@@ -27,7 +105,30 @@ impl MmrMeta {
             root_hash,
             mmr_size: U256::from(mmr_size),
             peaks,
+            peaks_validated: false,
+            hasher: PhantomData,
+        }
+    }
+
+    /// Checks the peaks against the root hash and the MMR size, once per snapshot.
+    ///
+    /// Later calls, and every later call to [`MmrMeta::verify_proof`], skip the check.
+    pub fn validate_peaks(&mut self) -> Result<(), MmrError> {
+        if !self.peaks_validated {
+            self.check_peaks()?;
+            self.peaks_validated = true;
         }
+        Ok(())
+    }
+
+    /// Returns the peaks of the MMR.
+    pub fn peaks(&self) -> &[B256] {
+        &self.peaks
+    }
+
+    /// Returns whether the peaks were checked by [`MmrMeta::validate_peaks`].
+    pub fn peaks_validated(&self) -> bool {
+        self.peaks_validated
     }
 
     /// Verifies a proof for an element in the MMR.
@@ -45,35 +146,25 @@ impl MmrMeta {
         element_hash: B256,
         proof: Vec<B256>,
     ) -> Result<bool, MmrError> {
-        let calculated_root = self.compute_bagged_peaks()?;
-        let mmr_size = self.mmr_size.to_string().parse::<usize>().unwrap();
-
-        if calculated_root != self.root_hash {
-            return Err(MmrError::InvalidRootHash);
-        }
-
-        let leaf_count = mmr_size_to_leaf_count(mmr_size);
-        let expected_peak_count = leaf_count_to_peak_count(leaf_count);
-
-        if expected_peak_count != self.peaks.len() as u32 {
-            return Err(MmrError::InvalidPeakCount);
-        }
+        let mmr_size = self.validated_size()?;
+        let element_index =
+            u64::try_from(element_index).map_err(|_| MmrError::ElementIndexOverflow)?;
 
         let mut current_hash = element_hash;
-        let mut leaf_index = element_index_to_leaf_index(element_index as usize)?;
+        let mut leaf_index = element_index_to_leaf_index(element_index)?;
 
         for proof_element in proof {
             let is_right = leaf_index % 2 == 1;
             current_hash = if is_right {
-                keccak256([proof_element, current_hash].concat())
+                H::hash_pair(proof_element, current_hash)
             } else {
-                keccak256([current_hash, proof_element].concat())
+                H::hash_pair(current_hash, proof_element)
             };
 
             leaf_index /= 2;
         }
 
-        let (peak_index, _) = get_peak_info(mmr_size, element_index as usize);
+        let (peak_index, _) = get_peak_info(mmr_size, element_index)?;
 
         if self.peaks[peak_index] == current_hash {
             Ok(true)
@@ -82,10 +173,153 @@ impl MmrMeta {
         }
     }
 
+    /// Verifies a multi-proof for many elements in the MMR.
+    ///
+    /// # Arguments
+    /// * `element_hashes` - The hashes of the elements being verified, in the order of
+    ///   `proof.element_indices`.
+    /// * `proof` - The multi-proof covering the elements.
+    ///
+    /// # Returns
+    /// A `Result` containing `true` if the proof is valid, or an [`MmrError`] otherwise.
+    pub fn verify_multi_proof(
+        &self,
+        element_hashes: &[B256],
+        proof: &MmrMultiProof,
+    ) -> Result<bool, MmrError> {
+        let mmr_size = self.validated_size()?;
+        if element_hashes.len() != proof.element_indices.len() {
+            return Err(MmrError::InvalidProof);
+        }
+
+        let mut hashes = element_hashes.iter();
+        let mut siblings = proof.siblings.iter();
+        for (peak_index, height, leaf_indices) in
+            leaves_by_mountain(mmr_size, &proof.element_indices)?
+        {
+            let mut level: Vec<(u64, B256)> = leaf_indices
+                .into_iter()
+                .zip(hashes.by_ref())
+                .map(|(leaf_index, hash)| (leaf_index, *hash))
+                .collect();
+
+            for _ in 0..height {
+                let mut parents = Vec::with_capacity(level.len());
+                let mut nodes = level.into_iter().peekable();
+                while let Some((index, hash)) = nodes.next() {
+                    let parent = if index % 2 == 1 {
+                        let left = siblings.next().ok_or(MmrError::InvalidProof)?;
+                        H::hash_pair(*left, hash)
+                    } else if let Some((_, right)) = nodes.next_if(|(next, _)| *next == index + 1) {
+                        H::hash_pair(hash, right)
+                    } else {
+                        let right = siblings.next().ok_or(MmrError::InvalidProof)?;
+                        H::hash_pair(hash, *right)
+                    };
+                    parents.push((index / 2, parent));
+                }
+                level = parents;
+            }
+
+            match level.as_slice() {
+                [(_, peak)] if self.peaks.get(peak_index) == Some(peak) => {}
+                _ => return Err(MmrError::InvalidProof),
+            }
+        }
+
+        if siblings.next().is_some() {
+            return Err(MmrError::InvalidProof);
+        }
+        Ok(true)
+    }
+
+    /// Verifies that this MMR is a prefix of the `newer` one, so that elements proven
+    /// against this snapshot are also in the newer one.
+    ///
+    /// # Arguments
+    /// * `newer` - The metadata of a newer snapshot of the same MMR.
+    /// * `proof` - The consistency proof between the two snapshots.
+    ///
+    /// # Returns
+    /// A `Result` containing `true` if the proof is valid, or an [`MmrError`] otherwise.
+    pub fn verify_consistency(
+        &self,
+        newer: &MmrMeta<H>,
+        proof: &MmrConsistencyProof,
+    ) -> Result<bool, MmrError> {
+        let old_size = self.validated_size()?;
+        let new_size = newer.validated_size()?;
+        if old_size > new_size {
+            return Err(MmrError::InvalidSize);
+        }
+        let old_mountains = mountains(mmr_size_to_leaf_count(old_size)?);
+        let new_mountains = mountains(mmr_size_to_leaf_count(new_size)?);
+        if proof.peak_paths.len() != old_mountains.len() {
+            return Err(MmrError::InvalidProof);
+        }
+
+        for ((peak, (first_leaf, height)), path) in
+            self.peaks.iter().zip(old_mountains).zip(&proof.peak_paths)
+        {
+            let (new_peak_index, new_height) = containing_mountain(&new_mountains, first_leaf)?;
+            if new_height < height || path.len() != (new_height - height) as usize {
+                return Err(MmrError::InvalidProof);
+            }
+
+            let mut current_hash = *peak;
+            let mut node_index = first_leaf >> height;
+            for sibling in path {
+                current_hash = if node_index % 2 == 1 {
+                    H::hash_pair(*sibling, current_hash)
+                } else {
+                    H::hash_pair(current_hash, *sibling)
+                };
+                node_index /= 2;
+            }
+
+            if newer.peaks[new_peak_index] != current_hash {
+                return Err(MmrError::InvalidProof);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns the MMR size, checking the peaks first unless they were validated.
+    fn validated_size(&self) -> Result<u64, MmrError> {
+        if self.peaks_validated {
+            self.mmr_size_u64()
+        } else {
+            self.check_peaks()
+        }
+    }
+
+    /// Checks the peaks against the root hash and the MMR size, returning the size.
+    fn check_peaks(&self) -> Result<u64, MmrError> {
+        let calculated_root = self.compute_bagged_peaks()?;
+        let mmr_size = self.mmr_size_u64()?;
+
+        if calculated_root != self.root_hash {
+            return Err(MmrError::InvalidRootHash);
+        }
+
+        let leaf_count = mmr_size_to_leaf_count(mmr_size)?;
+        let expected_peak_count = leaf_count_to_peak_count(leaf_count);
+
+        if expected_peak_count != self.peaks.len() as u32 {
+            return Err(MmrError::InvalidPeakCount);
+        }
+
+        Ok(mmr_size)
+    }
+
+    fn mmr_size_u64(&self) -> Result<u64, MmrError> {
+        u64::try_from(self.mmr_size).map_err(|_| MmrError::SizeOverflow)
+    }
+
     fn compute_bagged_peaks(&self) -> Result<B256, MmrError> {
         let final_peak = self.compute_final_peak()?;
         let size_hash: B256 = U256::from(self.mmr_size).into();
-        Ok(keccak256([size_hash, final_peak].concat()))
+        Ok(H::hash_pair(size_hash, final_peak))
     }
 
     fn compute_final_peak(&self) -> Result<B256, MmrError> {
@@ -101,14 +335,12 @@ impl MmrMeta {
 
         let last_peak = peak_hashes.pop().ok_or(MmrError::InvalidPeakCount)?;
         let second_last_peak = peak_hashes.pop().ok_or(MmrError::InvalidPeakCount)?;
-        let initial_root = keccak256([second_last_peak, last_peak].concat());
+        let initial_root = H::hash_pair(second_last_peak, last_peak);
 
         Ok(peak_hashes
             .into_iter()
             .rev()
-            .fold(initial_root, |prev, current| {
-                keccak256([current, prev].concat())
-            }))
+            .fold(initial_root, |prev, current| H::hash_pair(current, prev)))
     }
 
     /// P = Keccak256(N | Keccak256(N | Node(p1) | Node(p2) | Node(p3))), N = size, p = peaks
@@ -116,7 +348,7 @@ impl MmrMeta {
     fn bag_peaks(&self) -> B256 {
         let final_top_peak = self.final_top_peak();
         let size: B256 = U256::from(self.mmr_size).into();
-        keccak256([size, final_top_peak].concat())
+        H::hash_pair(size, final_top_peak)
     }
 
     #[cfg(test)]
@@ -129,19 +361,375 @@ impl MmrMeta {
             _ => {
                 let last = peaks_hashes.pop().unwrap();
                 let second_last = peaks_hashes.pop().unwrap();
-                let root0 = keccak256([second_last, last].concat());
+                let root0 = H::hash_pair(second_last, last);
 
                 peaks_hashes
                     .into_iter()
                     .rev()
-                    .fold(root0, |prev, cur| keccak256([cur, prev].concat()))
+                    .fold(root0, |prev, cur| H::hash_pair(cur, prev))
+            }
+        }
+    }
+}
+
+/// An append-only MMR kept in memory.
+///
+/// Nodes are hashed and peaks bagged as in [`MmrMeta`], so that its root and inclusion
+/// proofs are accepted by [`MmrMeta::verify_proof`].
+///
+/// Being append-only, it also answers for any earlier size: the elements of an earlier
+/// snapshot are a prefix of the current ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Mmr<H: MmrHasher = KeccakHasher> {
+    /// Every element, the element at index `i` being stored at `i - 1`.
+    elements: Vec<B256>,
+    leaf_count: u64,
+    #[serde(skip)]
+    hasher: PhantomData<H>,
+}
+
+impl<H: MmrHasher> Mmr<H> {
+    /// Creates a new empty [`Mmr`].
+    pub fn new() -> Self {
+        Self {
+            elements: Vec::new(),
+            leaf_count: 0,
+            hasher: PhantomData,
+        }
+    }
+
+    /// Returns the total number of elements in the MMR.
+    pub fn size(&self) -> u64 {
+        self.elements.len() as u64
+    }
+
+    /// Returns the number of leaves in the MMR.
+    pub fn leaf_count(&self) -> u64 {
+        self.leaf_count
+    }
+
+    /// Returns the element at `element_index`, counting from 1.
+    pub fn element(&self, element_index: u128) -> Option<B256> {
+        let position = usize::try_from(element_index.checked_sub(1)?).ok()?;
+        self.elements.get(position).copied()
+    }
+
+    /// Appends a leaf, such as a header hash, and returns its element index.
+    pub fn append(&mut self, leaf: B256) -> u128 {
+        let element_index = self.size() + 1;
+        // Every trailing one of the leaf index closes a mountain.
+        let merges = self.leaf_count.trailing_ones();
+
+        let mut node = leaf;
+        self.elements.push(node);
+        for height in 0..merges {
+            let left = self.elements[self.elements.len() - (1 << (height + 1))];
+            node = H::hash_pair(left, node);
+            self.elements.push(node);
+        }
+        self.leaf_count += 1;
+
+        u128::from(element_index)
+    }
+
+    /// Returns the peaks of the MMR, from the highest mountain to the lowest.
+    pub fn peaks(&self) -> Vec<B256> {
+        self.peaks_of(self.leaf_count)
+    }
+
+    /// Returns the peaks of the MMR when it had `mmr_size` elements.
+    pub fn peaks_at(&self, mmr_size: u64) -> Result<Vec<B256>, MmrError> {
+        if mmr_size > self.size() {
+            return Err(MmrError::InvalidSize);
+        }
+        Ok(self.peaks_of(mmr_size_to_leaf_count(mmr_size)?))
+    }
+
+    /// Returns the metadata of the MMR, with its bagged root, under the ID `mmr_id`.
+    pub fn meta(&self, mmr_id: U256) -> Result<MmrMeta<H>, MmrError> {
+        self.meta_at(mmr_id, self.size())
+    }
+
+    /// Returns the metadata of the MMR when it had `mmr_size` elements, under the ID `mmr_id`.
+    pub fn meta_at(&self, mmr_id: U256, mmr_size: u64) -> Result<MmrMeta<H>, MmrError> {
+        let mut meta = MmrMeta {
+            mmr_id,
+            root_hash: B256::ZERO,
+            mmr_size: U256::from(mmr_size),
+            peaks: self.peaks_at(mmr_size)?,
+            peaks_validated: false,
+            hasher: PhantomData,
+        };
+        meta.root_hash = meta.compute_bagged_peaks()?;
+        // The root is bagged from the peaks themselves.
+        meta.peaks_validated = true;
+        Ok(meta)
+    }
+
+    /// Returns the bagged root of the MMR.
+    pub fn root(&self) -> Result<B256, MmrError> {
+        Ok(self.meta(U256::ZERO)?.root_hash)
+    }
+
+    /// Returns the bagged root of the MMR when it had `mmr_size` elements.
+    pub fn root_at(&self, mmr_size: u64) -> Result<B256, MmrError> {
+        Ok(self.meta_at(U256::ZERO, mmr_size)?.root_hash)
+    }
+
+    /// Returns the inclusion proof of the leaf at `element_index`, in the format
+    /// accepted by [`MmrMeta::verify_proof`].
+    pub fn proof(&self, element_index: u128) -> Result<Vec<B256>, MmrError> {
+        self.proof_at(element_index, self.size())
+    }
+
+    /// Returns the inclusion proof of the leaf at `element_index` against the MMR
+    /// when it had `mmr_size` elements.
+    pub fn proof_at(&self, element_index: u128, mmr_size: u64) -> Result<Vec<B256>, MmrError> {
+        if mmr_size > self.size() {
+            return Err(MmrError::InvalidSize);
+        }
+        let element_index =
+            u64::try_from(element_index).map_err(|_| MmrError::ElementIndexOverflow)?;
+        let leaf_index = element_index_to_leaf_index(element_index)?;
+        let (_, height) = get_peak_info(mmr_size, element_index)?;
+        Ok(self.path(element_index, leaf_index, 0, height))
+    }
+
+    /// Returns the proof that the MMR when it had `old_size` elements is a prefix of the
+    /// MMR when it had `new_size` elements, in the format accepted by
+    /// [`MmrMeta::verify_consistency`].
+    pub fn consistency_proof(
+        &self,
+        old_size: u64,
+        new_size: u64,
+    ) -> Result<MmrConsistencyProof, MmrError> {
+        if new_size > self.size() || old_size > new_size {
+            return Err(MmrError::InvalidSize);
+        }
+        let old_mountains = mountains(mmr_size_to_leaf_count(old_size)?);
+        let new_mountains = mountains(mmr_size_to_leaf_count(new_size)?);
+
+        let mut peak_paths = Vec::with_capacity(old_mountains.len());
+        let mut element_index: u64 = 0;
+        for (first_leaf, height) in old_mountains {
+            // 2^(height + 1) - 1 elements, without overflowing for a height of 63
+            element_index += u64::MAX >> (u64::BITS - 1 - height);
+            let (_, new_height) = containing_mountain(&new_mountains, first_leaf)?;
+            peak_paths.push(self.path(element_index, first_leaf >> height, height, new_height));
+        }
+        Ok(MmrConsistencyProof { peak_paths })
+    }
+
+    /// Returns the siblings of the element at `element_index`, the `node_index`-th node of
+    /// height `from`, up to its ancestor of height `to`.
+    fn path(&self, mut element_index: u64, node_index: u64, from: u32, to: u32) -> Vec<B256> {
+        let mut path = Vec::with_capacity((to - from) as usize);
+        for level in from..to {
+            // Number of elements in a subtree of height `level`
+            let subtree_size = (1u64 << (level + 1)) - 1;
+            if (node_index >> (level - from)) & 1 == 1 {
+                path.push(self.elements[(element_index - subtree_size - 1) as usize]);
+                element_index += 1;
+            } else {
+                path.push(self.elements[(element_index + subtree_size - 1) as usize]);
+                element_index += subtree_size + 1;
             }
         }
+        path
+    }
+
+    /// Returns the peaks of the first `leaf_count` leaves.
+    fn peaks_of(&self, leaf_count: u64) -> Vec<B256> {
+        let mut peaks = Vec::with_capacity(leaf_count.count_ones() as usize);
+        let mut offset: u64 = 0;
+        for height in (0..u64::BITS).rev() {
+            if (leaf_count >> height) & 1 == 1 {
+                // 2^(height + 1) - 1 elements, without overflowing for a height of 63
+                offset += u64::MAX >> (u64::BITS - 1 - height);
+                peaks.push(self.elements[(offset - 1) as usize]);
+            }
+        }
+        peaks
     }
 }
 
-fn bit_length(value: usize) -> usize {
-    (std::mem::size_of::<usize>() * 8) - value.leading_zeros() as usize
+/// Inclusion proof for many elements of the MMR, sharing their sibling hashes.
+///
+/// Siblings that can be computed from the proven elements are left out, and every other
+/// sibling appears once, so that neighbouring elements cost far fewer hashes than
+/// separate proofs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MmrMultiProof {
+    /// Indices of the proven elements in the MMR, in ascending order.
+    pub element_indices: Vec<u128>,
+    /// Sibling hashes, mountain by mountain and level by level, from left to right.
+    pub siblings: Vec<B256>,
+}
+
+impl MmrMultiProof {
+    /// Builds a multi-proof from separate inclusion proofs of the same MMR.
+    ///
+    /// # Arguments
+    /// * `mmr` - The MMR the proofs belong to.
+    /// * `proofs` - The element indices, with their inclusion proofs.
+    pub fn new<H: MmrHasher>(
+        mmr: &MmrMeta<H>,
+        mut proofs: Vec<(u128, Vec<B256>)>,
+    ) -> Result<Self, MmrError> {
+        let mmr_size = mmr.mmr_size_u64()?;
+        proofs.sort_by_key(|(element_index, _)| *element_index);
+        proofs.dedup_by_key(|(element_index, _)| *element_index);
+        let element_indices: Vec<u128> = proofs
+            .iter()
+            .map(|(element_index, _)| *element_index)
+            .collect();
+
+        // Every sibling hash known from the separate proofs, by mountain, level and index
+        let mut known: HashMap<(usize, u32, u64), B256> = HashMap::new();
+        let mut proofs = proofs.into_iter();
+        let mountains = leaves_by_mountain(mmr_size, &element_indices)?;
+        for (peak_index, height, leaf_indices) in &mountains {
+            for (leaf_index, (_, proof)) in leaf_indices.iter().zip(proofs.by_ref()) {
+                if proof.len() != *height as usize {
+                    return Err(MmrError::InvalidProof);
+                }
+                for (level, sibling) in proof.into_iter().enumerate() {
+                    let level = level as u32;
+                    known.insert((*peak_index, level, (leaf_index >> level) ^ 1), sibling);
+                }
+            }
+        }
+
+        let mut siblings = Vec::new();
+        for (peak_index, height, leaf_indices) in mountains {
+            let mut indices = leaf_indices;
+            for level in 0..height {
+                let mut parents = Vec::with_capacity(indices.len());
+                let mut nodes = indices.into_iter().peekable();
+                while let Some(index) = nodes.next() {
+                    if index % 2 == 1 || nodes.next_if_eq(&(index + 1)).is_none() {
+                        let sibling = known
+                            .get(&(peak_index, level, index ^ 1))
+                            .ok_or(MmrError::InvalidProof)?;
+                        siblings.push(*sibling);
+                    }
+                    parents.push(index / 2);
+                }
+                indices = parents;
+            }
+        }
+
+        Ok(Self {
+            element_indices,
+            siblings,
+        })
+    }
+}
+
+/// Proof that an MMR snapshot is a prefix of a newer snapshot of the same MMR.
+///
+/// Every peak of the older snapshot is a node of the newer one, so the proof only holds
+/// the path from each older peak up to the newer peak above it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MmrConsistencyProof {
+    /// Sibling hashes from every peak of the older snapshot up to its peak in the newer
+    /// one, from the highest mountain to the lowest.
+    pub peak_paths: Vec<Vec<B256>>,
+}
+
+impl MmrConsistencyProof {
+    /// Returns the index of the first leaf of every mountain of the MMR when it had
+    /// `old_size` elements, from the highest mountain to the lowest.
+    pub fn mountain_leaves(old_size: u64) -> Result<Vec<u64>, MmrError> {
+        Ok(mountains(mmr_size_to_leaf_count(old_size)?)
+            .into_iter()
+            .map(|(first_leaf, _)| first_leaf)
+            .collect())
+    }
+
+    /// Builds the proof that the MMR when it had `old_size` elements is a prefix of a newer
+    /// snapshot, from the inclusion proofs against the newer snapshot of the leaves returned
+    /// by [`MmrConsistencyProof::mountain_leaves`], in that order.
+    ///
+    /// The path of a leaf up to its newer peak goes through the older peak above it, so the
+    /// siblings above the height of the older mountain are the path of the older peak.
+    pub fn from_inclusion_proofs(old_size: u64, proofs: Vec<Vec<B256>>) -> Result<Self, MmrError> {
+        let old_mountains = mountains(mmr_size_to_leaf_count(old_size)?);
+        if proofs.len() != old_mountains.len() {
+            return Err(MmrError::InvalidProof);
+        }
+        let peak_paths = old_mountains
+            .into_iter()
+            .zip(proofs)
+            .map(|((_, height), mut proof)| {
+                if proof.len() < height as usize {
+                    return Err(MmrError::InvalidProof);
+                }
+                Ok(proof.split_off(height as usize))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { peak_paths })
+    }
+}
+
+/// Groups the leaves at `element_indices` by mountain, as `(peak index, height, leaf indices)`.
+///
+/// The element indices must be strictly ascending.
+fn leaves_by_mountain(
+    mmr_size: u64,
+    element_indices: &[u128],
+) -> Result<Vec<(usize, u32, Vec<u64>)>, MmrError> {
+    let mut mountains: Vec<(usize, u32, Vec<u64>)> = Vec::new();
+    let mut previous = 0;
+    for element_index in element_indices {
+        let element_index =
+            u64::try_from(*element_index).map_err(|_| MmrError::ElementIndexOverflow)?;
+        if element_index <= previous {
+            return Err(MmrError::InvalidElementIndex);
+        }
+        previous = element_index;
+
+        let leaf_index = element_index_to_leaf_index(element_index)?;
+        let (peak_index, height) = get_peak_info(mmr_size, element_index)?;
+        match mountains.last_mut() {
+            Some((last_peak_index, _, leaf_indices)) if *last_peak_index == peak_index => {
+                leaf_indices.push(leaf_index)
+            }
+            _ => mountains.push((peak_index, height, vec![leaf_index])),
+        }
+    }
+    Ok(mountains)
+}
+
+/// Lists the mountains of an MMR with `leaf_count` leaves as `(first leaf index, height)`,
+/// from the highest to the lowest.
+fn mountains(leaf_count: u64) -> Vec<(u64, u32)> {
+    let mut mountains = Vec::with_capacity(leaf_count.count_ones() as usize);
+    let mut first_leaf: u64 = 0;
+    for height in (0..u64::BITS).rev() {
+        if (leaf_count >> height) & 1 == 1 {
+            mountains.push((first_leaf, height));
+            first_leaf += 1 << height;
+        }
+    }
+    mountains
+}
+
+/// Finds the mountain holding the leaf at `leaf_index`, as `(peak index, height)`.
+fn containing_mountain(
+    mountains: &[(u64, u32)],
+    leaf_index: u64,
+) -> Result<(usize, u32), MmrError> {
+    let peak_index = mountains
+        .iter()
+        .rposition(|(first_leaf, _)| *first_leaf <= leaf_index)
+        .ok_or(MmrError::InvalidSize)?;
+    Ok((peak_index, mountains[peak_index].1))
+}
+
+fn bit_length(value: u64) -> u32 {
+    u64::BITS - value.leading_zeros()
 }
 
 /// Retrieves the peak index and height for a given element.
@@ -149,15 +737,20 @@ fn bit_length(value: usize) -> usize {
 /// # Arguments
 /// * `element_count` - Total number of elements in the MMR.
 /// * `element_index` - The index of the element to find peak information for.
-fn get_peak_info(mut element_count: usize, mut element_index: usize) -> (usize, usize) {
+fn get_peak_info(mut element_count: u64, mut element_index: u64) -> Result<(usize, u32), MmrError> {
+    if element_index == 0 || element_index > element_count {
+        return Err(MmrError::InvalidElementIndex);
+    }
+
     let mut mountain_height = bit_length(element_count);
-    let mut mountain_size = (1 << mountain_height) - 1;
+    // (1 << height) - 1, without overflowing for a height of 64
+    let mut mountain_size = u64::MAX >> (u64::BITS - mountain_height);
     let mut mountain_index = 0;
 
-    loop {
+    while mountain_height > 0 {
         if mountain_size <= element_count {
             if element_index <= mountain_size {
-                return (mountain_index, mountain_height - 1);
+                return Ok((mountain_index, mountain_height - 1));
             }
             element_count -= mountain_size;
             element_index -= mountain_size;
@@ -166,55 +759,63 @@ fn get_peak_info(mut element_count: usize, mut element_index: usize) -> (usize,
         mountain_size >>= 1;
         mountain_height -= 1;
     }
-}
 
-fn leaf_count_to_peak_count(leaf_count: usize) -> u32 {
-    count_ones(leaf_count) as u32
+    Err(MmrError::InvalidSize)
 }
 
-fn count_ones(mut value: usize) -> usize {
-    let mut count = 0;
-    while value > 0 {
-        value &= value - 1;
-        count += 1;
-    }
-    count
+fn leaf_count_to_peak_count(leaf_count: u64) -> u32 {
+    leaf_count.count_ones()
 }
 
-fn mmr_size_to_leaf_count(mmr_size: usize) -> usize {
+fn mmr_size_to_leaf_count(mmr_size: u64) -> Result<u64, MmrError> {
     let mut remaining_size = mmr_size;
-    let bits = bit_length(remaining_size + 1);
-    let mut tip_size = 1 << (bits - 1);
-    let mut leaf_count = 0;
+    let bits = bit_length(mmr_size.checked_add(1).ok_or(MmrError::SizeOverflow)?);
+    let mut tip_size: u64 = 1 << (bits - 1);
+    let mut leaf_count: u64 = 0;
 
     while tip_size != 0 {
-        let mountain_size = 2 * tip_size - 1;
+        // 2 * tip_size - 1, without overflowing for a tip of 2^63 leaves
+        let mountain_size = tip_size + (tip_size - 1);
         if mountain_size <= remaining_size {
             remaining_size -= mountain_size;
-            leaf_count += tip_size;
+            leaf_count = leaf_count
+                .checked_add(tip_size)
+                .ok_or(MmrError::ArithmeticOverflow)?;
         }
         tip_size >>= 1;
     }
 
-    leaf_count
+    if remaining_size > 0 {
+        Err(MmrError::InvalidSize)
+    } else {
+        Ok(leaf_count)
+    }
 }
 
-fn element_index_to_leaf_index(element_index: usize) -> Result<usize, MmrError> {
+/// Returns the index of the leaf at `element_index`, counting leaves from 0.
+pub fn element_index_to_leaf_index(element_index: u64) -> Result<u64, MmrError> {
     if element_index == 0 {
         return Err(MmrError::InvalidElementIndex);
     }
     count_elements_to_leaf_count(element_index - 1)
 }
 
-fn count_elements_to_leaf_count(element_count: usize) -> Result<usize, MmrError> {
-    let mut leaf_count = 0;
-    let mut mountain_leaf_count = 1 << bit_length(element_count);
+fn count_elements_to_leaf_count(element_count: u64) -> Result<u64, MmrError> {
+    if element_count == 0 {
+        return Ok(0);
+    }
+
+    let mut leaf_count: u64 = 0;
+    // The largest mountain fitting in `element_count` elements has at most this many leaves.
+    let mut mountain_leaf_count: u64 = 1 << (bit_length(element_count) - 1);
     let mut remaining_elements = element_count;
 
     while mountain_leaf_count > 0 {
-        let mountain_size = 2 * mountain_leaf_count - 1;
+        let mountain_size = mountain_leaf_count + (mountain_leaf_count - 1);
         if mountain_size <= remaining_elements {
-            leaf_count += mountain_leaf_count;
+            leaf_count = leaf_count
+                .checked_add(mountain_leaf_count)
+                .ok_or(MmrError::ArithmeticOverflow)?;
             remaining_elements -= mountain_size;
         }
         mountain_leaf_count >>= 1;
@@ -257,6 +858,18 @@ pub enum MmrError {
     /// Error decoding the data
     #[error("Error decoding the data")]
     DecodingError,
+
+    /// MMR size does not fit in 64 bits
+    #[error("MMR size does not fit in 64 bits")]
+    SizeOverflow,
+
+    /// Element index does not fit in 64 bits
+    #[error("Element index does not fit in 64 bits")]
+    ElementIndexOverflow,
+
+    /// Arithmetic overflow while computing MMR positions
+    #[error("Arithmetic overflow while computing MMR positions")]
+    ArithmeticOverflow,
 }
 
 /// Represents a header along with its inclusion proof in the MMR.
@@ -346,6 +959,8 @@ mod tests {
                 )
                 .unwrap(),
             ],
+            peaks_validated: false,
+            hasher: PhantomData,
         };
 
         let bag = test_mmr_meta.bag_peaks();
@@ -372,6 +987,8 @@ mod tests {
                 .unwrap(),
                 B256::from(U256::from(3)),
             ],
+            peaks_validated: false,
+            hasher: PhantomData,
         };
 
         assert!(test_mmr_meta
@@ -404,6 +1021,8 @@ mod tests {
                 b256!("6fdbe7ef87553b453ef0c66322a33575f1e92b00d2abca122f9d9caeddca03b7"),
                 b256!("45da6302e5933720e03c6f851000ac3605ca863c54839c265eadc252bf7c4764"),
             ],
+            peaks_validated: false,
+            hasher: PhantomData,
         };
 
         let test_header = Header {
@@ -439,4 +1058,423 @@ mod tests {
 
         assert!(verify_headers_with_mmr_peaks(test_mmr_meta, &[test_header]).unwrap());
     }
+
+    /// Size of an MMR with `leaf_count` leaves.
+    fn mmr_size(leaf_count: u64) -> u64 {
+        2 * leaf_count - leaf_count.count_ones() as u64
+    }
+
+    /// Element index of the leaf at `leaf_index`, counting from 1.
+    fn leaf_element_index(leaf_index: u64) -> u64 {
+        mmr_size(leaf_index) + 1
+    }
+
+    #[test]
+    fn test_large_mmr_indices() {
+        // Well beyond the 32-bit range of the zkVM target
+        for leaf_count in [(1 << 32) + 1, (1 << 40) + 8, (1 << 62) + 12345] {
+            assert_eq!(
+                mmr_size_to_leaf_count(mmr_size(leaf_count)).unwrap(),
+                leaf_count
+            );
+        }
+        for leaf_index in [1 << 32, (1 << 40) + 7, (1 << 62) + 12345] {
+            assert_eq!(
+                element_index_to_leaf_index(leaf_element_index(leaf_index)).unwrap(),
+                leaf_index
+            );
+        }
+
+        // A mountain of 2^40 leaves followed by a mountain of 8 leaves
+        let leaf_count = (1 << 40) + 8;
+        let size = mmr_size(leaf_count);
+        assert_eq!(
+            get_peak_info(size, leaf_element_index(12345)).unwrap(),
+            (0, 40)
+        );
+        assert_eq!(
+            get_peak_info(size, leaf_element_index(leaf_count - 1)).unwrap(),
+            (1, 3)
+        );
+        assert_eq!(get_peak_info(size, size).unwrap(), (1, 3));
+    }
+
+    #[test]
+    fn test_mmr_overflow() {
+        assert!(matches!(
+            mmr_size_to_leaf_count(u64::MAX),
+            Err(MmrError::SizeOverflow)
+        ));
+        assert!(matches!(
+            mmr_size_to_leaf_count(2),
+            Err(MmrError::InvalidSize)
+        ));
+        assert!(matches!(
+            get_peak_info(11, 12),
+            Err(MmrError::InvalidElementIndex)
+        ));
+
+        let test_mmr_meta: MmrMeta = MmrMeta {
+            mmr_id: U256::ZERO,
+            root_hash: B256::ZERO,
+            mmr_size: U256::from(u64::MAX) + U256::from(1),
+            peaks: vec![B256::ZERO],
+            peaks_validated: false,
+            hasher: PhantomData,
+        };
+        assert!(matches!(
+            test_mmr_meta.verify_proof(1, B256::ZERO, vec![]),
+            Err(MmrError::SizeOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_verify_proof_large_mmr() {
+        // A single mountain of 2^40 leaves
+        let leaf_index: u64 = 0x12_3456_789a;
+        let element_hash = B256::from(U256::from(0xdead_beef_u64));
+        let proof: Vec<B256> = (0..40u64).map(|i| B256::from(U256::from(i))).collect();
+
+        let mut peak = element_hash;
+        for (height, sibling) in proof.iter().enumerate() {
+            peak = if (leaf_index >> height) & 1 == 1 {
+                keccak256([*sibling, peak].concat())
+            } else {
+                keccak256([peak, *sibling].concat())
+            };
+        }
+        let size = U256::from(mmr_size(1 << 40));
+        let size_hash: B256 = size.into();
+
+        let test_mmr_meta: MmrMeta = MmrMeta {
+            mmr_id: U256::ZERO,
+            root_hash: keccak256([size_hash, peak].concat()),
+            mmr_size: size,
+            peaks: vec![peak],
+            peaks_validated: false,
+            hasher: PhantomData,
+        };
+        assert!(test_mmr_meta
+            .verify_proof(
+                u128::from(leaf_element_index(leaf_index)),
+                element_hash,
+                proof.clone(),
+            )
+            .unwrap());
+        assert!(matches!(
+            test_mmr_meta.verify_proof(u128::from(u64::MAX) + 1, element_hash, proof),
+            Err(MmrError::ElementIndexOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_validate_peaks_once() {
+        let mut test_mmr_meta: MmrMeta = MmrMeta::new(
+            "0x0".to_string(),
+            b256!("a7122a01868e54648facd92a3a821fae03301a71d1bd02fabe4e82bffcbd0aeb"),
+            11,
+            vec![
+                b256!("bf874bd367f32d74d7d084a8eb85ce99d6f2622fbc0d1f83dcd0c4404f8e0cea"),
+                b256!("04cde762ef08b6b6c5ded8e8c4c0b3f4e5c9ad7342c88fcc93681b4588b73f05"),
+                B256::from(U256::from(3)),
+            ],
+        );
+        assert!(!test_mmr_meta.peaks_validated());
+        test_mmr_meta.validate_peaks().unwrap();
+        assert!(test_mmr_meta.peaks_validated());
+
+        // Once validated, proofs no longer bag the peaks against the root
+        test_mmr_meta.root_hash = B256::ZERO;
+        assert!(test_mmr_meta
+            .verify_proof(
+                8,
+                B256::from(U256::from(5)),
+                vec![B256::from(U256::from(4))],
+            )
+            .unwrap());
+
+        let mut invalid_mmr_meta: MmrMeta = MmrMeta::new(
+            "0x0".to_string(),
+            B256::ZERO,
+            11,
+            test_mmr_meta.peaks.clone(),
+        );
+        assert!(matches!(
+            invalid_mmr_meta.validate_peaks(),
+            Err(MmrError::InvalidRootHash)
+        ));
+        assert!(!invalid_mmr_meta.peaks_validated());
+    }
+
+    #[test]
+    fn test_mmr_append() {
+        // Six zero leaves give the MMR of `test_bag_peaks`
+        let mut mmr: Mmr = Mmr::new();
+        for _ in 0..6 {
+            mmr.append(B256::ZERO);
+        }
+        assert_eq!(mmr.size(), 10);
+        assert_eq!(mmr.leaf_count(), 6);
+        assert_eq!(
+            mmr.peaks(),
+            vec![
+                b256!("b4c11951957c6f8f642c4af61cd6b24640fec6dc7fc607ee8206a99e92410d30"),
+                b256!("ad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5"),
+            ]
+        );
+        assert_eq!(
+            mmr.root().unwrap(),
+            b256!("00367542437d21fb3d94c5449b6f6e650c4b4f8f307c2d4aa3a782f17a4ddd03")
+        );
+
+        // Every leaf proves against the metadata of every size
+        let mut mmr: Mmr = Mmr::new();
+        let mut element_indices = Vec::new();
+        for leaf in 0..40u64 {
+            element_indices.push(mmr.append(keccak256(leaf.to_be_bytes())));
+            let meta = mmr.meta(U256::from(1)).unwrap();
+            for element_index in &element_indices {
+                let element_hash = mmr.element(*element_index).unwrap();
+                let proof = mmr.proof(*element_index).unwrap();
+                assert!(meta
+                    .verify_proof(*element_index, element_hash, proof)
+                    .unwrap());
+            }
+        }
+
+        // Only leaves have inclusion proofs
+        assert!(mmr.proof(3).is_err());
+        assert!(mmr.proof(u128::from(mmr.size()) + 1).is_err());
+        assert!(matches!(
+            Mmr::<KeccakHasher>::new().root(),
+            Err(MmrError::InvalidPeakCount)
+        ));
+    }
+
+    #[test]
+    fn test_verify_multi_proof() {
+        let mut mmr: Mmr = Mmr::new();
+        let element_indices: Vec<u128> = (0..27u64)
+            .map(|leaf| mmr.append(keccak256(leaf.to_be_bytes())))
+            .collect();
+        let test_mmr_meta = mmr.meta(U256::ZERO).unwrap();
+
+        // A contiguous range across two mountains, plus leaves of the last mountains
+        let proofs: Vec<(u128, Vec<B256>)> = (13..20)
+            .chain([24, 26])
+            .map(|leaf_index| {
+                let element_index = element_indices[leaf_index];
+                (element_index, mmr.proof(element_index).unwrap())
+            })
+            .collect();
+        let separate_siblings: usize = proofs.iter().map(|(_, proof)| proof.len()).sum();
+
+        let multi_proof =
+            MmrMultiProof::new(&test_mmr_meta, proofs.into_iter().rev().collect()).unwrap();
+        assert!(multi_proof.siblings.len() < separate_siblings);
+        let element_hashes: Vec<B256> = multi_proof
+            .element_indices
+            .iter()
+            .map(|element_index| mmr.element(*element_index).unwrap())
+            .collect();
+        assert!(test_mmr_meta
+            .verify_multi_proof(&element_hashes, &multi_proof)
+            .unwrap());
+
+        // A wrong element hash leads to a wrong peak
+        let mut wrong_hashes = element_hashes.clone();
+        wrong_hashes[3] = B256::ZERO;
+        assert!(matches!(
+            test_mmr_meta.verify_multi_proof(&wrong_hashes, &multi_proof),
+            Err(MmrError::InvalidProof)
+        ));
+
+        // Leftover siblings are rejected
+        let mut padded_proof = multi_proof.clone();
+        padded_proof.siblings.push(B256::ZERO);
+        assert!(matches!(
+            test_mmr_meta.verify_multi_proof(&element_hashes, &padded_proof),
+            Err(MmrError::InvalidProof)
+        ));
+    }
+
+    #[test]
+    fn test_verify_proof_poseidon() {
+        // Three leaves: a mountain of two leaves and a single leaf
+        let leaves: Vec<B256> = (1..=3u64)
+            .map(|leaf| B256::from(U256::from(leaf)))
+            .collect();
+        let parent = PoseidonHasher::hash_pair(leaves[0], leaves[1]);
+        let size: B256 = U256::from(4).into();
+        let root = PoseidonHasher::hash_pair(size, PoseidonHasher::hash_pair(parent, leaves[2]));
+
+        assert_ne!(parent, KeccakHasher::hash_pair(leaves[0], leaves[1]));
+
+        let mmr: MmrMeta<PoseidonHasher> =
+            MmrMeta::new("0x1".to_string(), root, 4, vec![parent, leaves[2]]);
+        assert!(mmr.verify_proof(2, leaves[1], vec![leaves[0]]).unwrap());
+        assert!(mmr.verify_proof(4, leaves[2], vec![]).unwrap());
+
+        // The same MMR read as a keccak MMR does not match its root
+        let keccak_mmr: MmrMeta = MmrMeta::new("0x1".to_string(), root, 4, vec![parent, leaves[2]]);
+        assert!(matches!(
+            keccak_mmr.verify_proof(2, leaves[1], vec![leaves[0]]),
+            Err(MmrError::InvalidRootHash)
+        ));
+    }
+
+    #[test]
+    fn test_hash_leaf_poseidon() {
+        // Nine bytes make a full word and a partial one
+        let data = [1, 0, 0, 0, 0, 0, 0, 0, 2];
+        let words = [Felt::from(1u64), Felt::from(2u64)];
+        assert_eq!(
+            PoseidonHasher::hash_leaf(&data),
+            B256::from(poseidon_hash_many(&words).to_bytes_be())
+        );
+        assert_eq!(KeccakHasher::hash_leaf(&data), keccak256(data));
+    }
+
+    #[test]
+    fn test_mmr_historical_size() {
+        let mut mmr: Mmr = Mmr::new();
+        let mut snapshots = Vec::new();
+        for leaf in 0..20u64 {
+            let element_index = mmr.append(keccak256(leaf.to_be_bytes()));
+            snapshots.push((element_index, mmr.size(), mmr.root().unwrap()));
+        }
+
+        // Every leaf proves against every snapshot that includes it
+        for (i, (_, mmr_size, root)) in snapshots.iter().enumerate() {
+            let meta = mmr.meta_at(U256::from(1), *mmr_size).unwrap();
+            assert_eq!(meta.root_hash, *root);
+            assert_eq!(mmr.root_at(*mmr_size).unwrap(), *root);
+            for (element_index, _, _) in &snapshots[..=i] {
+                let proof = mmr.proof_at(*element_index, *mmr_size).unwrap();
+                let element_hash = mmr.element(*element_index).unwrap();
+                assert!(meta
+                    .verify_proof(*element_index, element_hash, proof)
+                    .unwrap());
+            }
+        }
+
+        // Sizes that are not snapshots, or beyond the MMR, are rejected
+        assert!(matches!(mmr.peaks_at(2), Err(MmrError::InvalidSize)));
+        assert!(matches!(
+            mmr.peaks_at(mmr.size() + 1),
+            Err(MmrError::InvalidSize)
+        ));
+        assert!(mmr.proof_at(snapshots[5].0, snapshots[4].1).is_err());
+    }
+
+    #[test]
+    fn test_verify_consistency() {
+        let mut mmr: Mmr = Mmr::new();
+        let sizes: Vec<u64> = (0..24u64)
+            .map(|leaf| {
+                mmr.append(keccak256(leaf.to_be_bytes()));
+                mmr.size()
+            })
+            .collect();
+
+        // Every snapshot is a prefix of every later one
+        for (i, old_size) in sizes.iter().enumerate() {
+            let older: MmrMeta = MmrMeta::new(
+                "0x1".to_string(),
+                mmr.root_at(*old_size).unwrap(),
+                u128::from(*old_size),
+                mmr.peaks_at(*old_size).unwrap(),
+            );
+            for new_size in &sizes[i..] {
+                let newer = mmr.meta_at(U256::from(1), *new_size).unwrap();
+                let proof = mmr.consistency_proof(*old_size, *new_size).unwrap();
+                assert!(older.verify_consistency(&newer, &proof).unwrap());
+            }
+        }
+
+        let older = mmr.meta_at(U256::from(1), sizes[10]).unwrap();
+        let newer = mmr.meta_at(U256::from(1), sizes[22]).unwrap();
+        let proof = mmr.consistency_proof(sizes[10], sizes[22]).unwrap();
+        assert!(proof.peak_paths.iter().any(|path| !path.is_empty()));
+
+        // A wrong sibling leads to a wrong peak
+        let mut wrong_proof = proof.clone();
+        let path = wrong_proof
+            .peak_paths
+            .iter_mut()
+            .find(|path| !path.is_empty())
+            .unwrap();
+        path[0] = B256::ZERO;
+        assert!(matches!(
+            older.verify_consistency(&newer, &wrong_proof),
+            Err(MmrError::InvalidProof)
+        ));
+
+        // A missing path is rejected
+        let mut short_proof = proof.clone();
+        short_proof.peak_paths.pop();
+        assert!(matches!(
+            older.verify_consistency(&newer, &short_proof),
+            Err(MmrError::InvalidProof)
+        ));
+
+        // A newer snapshot cannot be a prefix of an older one
+        assert!(matches!(
+            newer.verify_consistency(&older, &proof),
+            Err(MmrError::InvalidSize)
+        ));
+        assert!(matches!(
+            mmr.consistency_proof(sizes[22], sizes[10]),
+            Err(MmrError::InvalidSize)
+        ));
+
+        // Snapshots of another MMR are not consistent with this one
+        let mut other_mmr: Mmr = Mmr::new();
+        for leaf in 0..23u64 {
+            other_mmr.append(keccak256((leaf + 1).to_be_bytes()));
+        }
+        let other = other_mmr.meta_at(U256::from(1), sizes[22]).unwrap();
+        assert!(matches!(
+            older.verify_consistency(&other, &proof),
+            Err(MmrError::InvalidProof)
+        ));
+    }
+
+    #[test]
+    fn test_consistency_from_inclusion_proofs() {
+        let mut mmr: Mmr = Mmr::new();
+        let (element_indices, sizes): (Vec<u128>, Vec<u64>) = (0..24u64)
+            .map(|leaf| (mmr.append(keccak256(leaf.to_be_bytes())), mmr.size()))
+            .unzip();
+
+        // Inclusion proofs of a leaf per older mountain give the same proof as the full MMR
+        for (i, old_size) in sizes.iter().enumerate() {
+            for new_size in &sizes[i..] {
+                let proofs = MmrConsistencyProof::mountain_leaves(*old_size)
+                    .unwrap()
+                    .into_iter()
+                    .map(|leaf| {
+                        mmr.proof_at(element_indices[leaf as usize], *new_size)
+                            .unwrap()
+                    })
+                    .collect();
+                assert_eq!(
+                    MmrConsistencyProof::from_inclusion_proofs(*old_size, proofs).unwrap(),
+                    mmr.consistency_proof(*old_size, *new_size).unwrap()
+                );
+            }
+        }
+
+        // One inclusion proof per older mountain is required
+        assert!(matches!(
+            MmrConsistencyProof::from_inclusion_proofs(sizes[10], Vec::new()),
+            Err(MmrError::InvalidProof)
+        ));
+        for (leaf, element_index) in element_indices.iter().enumerate() {
+            assert_eq!(
+                element_index_to_leaf_index(*element_index as u64).unwrap(),
+                leaf as u64
+            );
+        }
+    }
 }
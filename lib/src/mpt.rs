@@ -2,8 +2,8 @@ use alloy_consensus::Account;
 use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
 use alloy_rlp::encode_fixed_size;
 use alloy_trie::{
-    proof::{verify_proof, ProofVerificationError},
-    Nibbles,
+    proof::{verify_proof, ProofRetainer, ProofVerificationError},
+    HashBuilder, Nibbles,
 };
 use thiserror_no_std::Error;
 
@@ -28,40 +28,84 @@ impl Mpt {
     ///
     /// # Arguments
     /// * `tx_index` - The index of the transaction.
+    /// * `tx_encoded` - The EIP-2718 encoding of the transaction expected at `tx_index`.
     /// * `proof` - The proof elements required to verify the transaction.
     ///
     /// # Returns
     /// A `Result` which is `Ok(())` if the proof is valid, or an [`MptError`] otherwise.
-    pub fn verify_transaction(&self, tx_index: u64, proof: Vec<Bytes>) -> Result<(), MptError> {
+    pub fn verify_transaction(
+        &self,
+        tx_index: u64,
+        tx_encoded: &[u8],
+        proof: Vec<Bytes>,
+    ) -> Result<(), MptError> {
         let nibbles = Nibbles::unpack(Bytes::from(alloy_rlp::encode(U256::from(tx_index))));
-        // TODO: last element of proof is the value of the key, not sure if it's ok to hardcode split prefix
-        let expected_value = proof
-            .last()
-            .and_then(|bytes| bytes.get(5..))
-            .ok_or(MptError::InvalidProof)?;
-        verify_proof(self.root, nibbles, Some(expected_value.to_vec()), &proof)
+        verify_proof(self.root, nibbles, Some(tx_encoded.to_vec()), &proof)
             .map_err(MptError::ProofVerification)
     }
 
+    /// Verifies that no transaction exists at the given index in the MPT using an exclusion proof.
+    ///
+    /// # Arguments
+    /// * `tx_index` - The index that must be absent from the transaction trie.
+    /// * `proof` - The proof elements for the path to `tx_index`.
+    ///
+    /// # Returns
+    /// A `Result` which is `Ok(())` if the index is proven absent, or an [`MptError`] otherwise.
+    pub fn verify_transaction_exclusion(
+        &self,
+        tx_index: u64,
+        proof: Vec<Bytes>,
+    ) -> Result<(), MptError> {
+        let nibbles = Nibbles::unpack(Bytes::from(alloy_rlp::encode(U256::from(tx_index))));
+        verify_proof(self.root, nibbles, None, &proof).map_err(MptError::ProofVerification)
+    }
+
     /// Verifies a receipt in the MPT using a proof.
     ///
     /// # Arguments
     /// * `tx_index` - The index of the transaction's receipt.
+    /// * `receipt_encoded` - The EIP-2718 encoding of the receipt expected at `tx_index`.
     /// * `proof` - The proof elements required to verify the receipt.
     ///
     /// # Returns
     /// A `Result` which is `Ok(())` if the proof is valid, or an [`MptError`] otherwise.
-    pub fn verify_receipt(&self, tx_index: u64, proof: Vec<Bytes>) -> Result<(), MptError> {
+    pub fn verify_receipt(
+        &self,
+        tx_index: u64,
+        receipt_encoded: &[u8],
+        proof: Vec<Bytes>,
+    ) -> Result<(), MptError> {
         let nibbles = Nibbles::unpack(Bytes::from(alloy_rlp::encode(U256::from(tx_index))));
-        // TODO: last element of proof is the value of the key, not sure if it's ok to hardcode split prefix
-        let expected_value = proof
-            .last()
-            .and_then(|bytes| bytes.get(7..))
-            .ok_or(MptError::InvalidProof)?;
-        verify_proof(self.root, nibbles, Some(expected_value.to_vec()), &proof)
+        verify_proof(self.root, nibbles, Some(receipt_encoded.to_vec()), &proof)
             .map_err(MptError::ProofVerification)
     }
 
+    /// Verifies a withdrawal in the MPT using a proof.
+    ///
+    /// # Arguments
+    /// * `withdrawal_index` - The index of the withdrawal within the block.
+    /// * `withdrawal_encoded` - The RLP encoding of the withdrawal expected at `withdrawal_index`.
+    /// * `proof` - The proof elements required to verify the withdrawal.
+    ///
+    /// # Returns
+    /// A `Result` which is `Ok(())` if the proof is valid, or an [`MptError`] otherwise.
+    pub fn verify_withdrawal(
+        &self,
+        withdrawal_index: u64,
+        withdrawal_encoded: &[u8],
+        proof: Vec<Bytes>,
+    ) -> Result<(), MptError> {
+        let nibbles = Nibbles::unpack(Bytes::from(alloy_rlp::encode(U256::from(withdrawal_index))));
+        verify_proof(
+            self.root,
+            nibbles,
+            Some(withdrawal_encoded.to_vec()),
+            &proof,
+        )
+        .map_err(MptError::ProofVerification)
+    }
+
     /// Verifies an account in the MPT using a proof.
     ///
     /// # Arguments
@@ -83,6 +127,23 @@ impl Mpt {
             .map_err(MptError::ProofVerification)
     }
 
+    /// Verifies that an account does not exist in the MPT using an exclusion proof.
+    ///
+    /// # Arguments
+    /// * `proof` - The proof elements for the path to the account.
+    /// * `address` - The address that must be absent from the state trie.
+    ///
+    /// # Returns
+    /// A `Result` which is `Ok(())` if the account is proven absent, or an [`MptError`] otherwise.
+    pub fn verify_account_exclusion(
+        &self,
+        proof: Vec<Bytes>,
+        address: Address,
+    ) -> Result<(), MptError> {
+        let nibbles = Nibbles::unpack(keccak256(address));
+        verify_proof(self.root, nibbles, None, &proof).map_err(MptError::ProofVerification)
+    }
+
     /// Verifies a storage value in the MPT using a proof.
     ///
     /// # Arguments
@@ -108,6 +169,40 @@ impl Mpt {
     }
 }
 
+/// Builds an index-keyed trie, as used for the transactions, receipts and withdrawals of a block,
+/// and returns its root together with the proof for the item at `index`.
+///
+/// # Arguments
+/// * `items` - The encoded trie values, ordered by index.
+/// * `index` - The index of the item to prove.
+pub fn ordered_trie_with_proof(items: &[Bytes], index: u64) -> (B256, Vec<Bytes>) {
+    let index_key = |i: u64| Nibbles::unpack(Bytes::from(alloy_rlp::encode(U256::from(i))));
+
+    // The hash builder expects leaves in key order, which differs from index order.
+    let mut leaves: Vec<(Nibbles, &Bytes)> = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (index_key(i as u64), item))
+        .collect();
+    leaves.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let target = index_key(index);
+    let mut hash_builder =
+        HashBuilder::default().with_proof_retainer(ProofRetainer::new(vec![target.clone()]));
+    for (key, value) in leaves {
+        hash_builder.add_leaf(key, value);
+    }
+    let root = hash_builder.root();
+    let proof = hash_builder
+        .take_proof_nodes()
+        .matching_nodes_sorted(&target)
+        .into_iter()
+        .map(|(_, node)| node)
+        .collect();
+
+    (root, proof)
+}
+
 /// Error types that may occur during MPT operations.
 #[derive(Debug, Error)]
 pub enum MptError {
@@ -119,3 +214,109 @@ pub enum MptError {
     #[error("Invalid proof")]
     InvalidProof,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip4895::Withdrawal;
+    use alloy_primitives::address;
+
+    #[test]
+    fn test_verify_transaction_exclusion() {
+        let items: Vec<Bytes> = (0..130u64)
+            .map(|i| Bytes::from(alloy_rlp::encode(U256::from(i))))
+            .collect();
+
+        let (root, proof) = ordered_trie_with_proof(&items, 130);
+        Mpt::new(root)
+            .verify_transaction_exclusion(130, proof)
+            .unwrap();
+
+        let (root, proof) = ordered_trie_with_proof(&items, 129);
+        assert!(Mpt::new(root)
+            .verify_transaction_exclusion(129, proof)
+            .is_err());
+
+        let (root, proof) = ordered_trie_with_proof(&[], 0);
+        Mpt::new(root)
+            .verify_transaction_exclusion(0, proof)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_verify_withdrawal() {
+        let withdrawals: Vec<Bytes> = (0..200)
+            .map(|i| {
+                alloy_rlp::encode(Withdrawal {
+                    index: 40_000_000 + i,
+                    validator_index: 1_000 + i,
+                    address: address!("7f2c6f930306d3aa736b3a6c6a98f512f74036d4"),
+                    amount: 18_000_000 + i,
+                })
+                .into()
+            })
+            .collect();
+
+        for index in [0, 1, 127, 128, 199] {
+            let (root, proof) = ordered_trie_with_proof(&withdrawals, index);
+            let mpt = Mpt::new(root);
+            mpt.verify_withdrawal(index, &withdrawals[index as usize], proof.clone())
+                .unwrap();
+            assert!(mpt
+                .verify_withdrawal(index, &withdrawals[(index as usize + 1) % 200], proof)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_verify_account_exclusion() {
+        let present = address!("75cec1db9dceb703200eaa6595f66885c962b920");
+        let absent = address!("7f2c6f930306d3aa736b3a6c6a98f512f74036d4");
+        let account = Account {
+            nonce: 1,
+            ..Default::default()
+        };
+
+        let build = |target: Address| {
+            let target = Nibbles::unpack(keccak256(target));
+            let mut leaves: Vec<(Nibbles, Vec<u8>)> = (1..=16u8)
+                .map(Address::with_last_byte)
+                .chain([present])
+                .map(|address| {
+                    (
+                        Nibbles::unpack(keccak256(address)),
+                        alloy_rlp::encode(account),
+                    )
+                })
+                .collect();
+            leaves.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            let mut hash_builder = HashBuilder::default()
+                .with_proof_retainer(ProofRetainer::new(vec![target.clone()]));
+            for (key, value) in leaves {
+                hash_builder.add_leaf(key, &value);
+            }
+            let root = hash_builder.root();
+            let proof: Vec<Bytes> = hash_builder
+                .take_proof_nodes()
+                .matching_nodes_sorted(&target)
+                .into_iter()
+                .map(|(_, node)| node)
+                .collect();
+            (root, proof)
+        };
+
+        let (root, proof) = build(absent);
+        Mpt::new(root)
+            .verify_account_exclusion(proof, absent)
+            .unwrap();
+
+        let (root, proof) = build(present);
+        assert!(Mpt::new(root)
+            .verify_account_exclusion(proof.clone(), present)
+            .is_err());
+        Mpt::new(root)
+            .verify_account(proof, account, present)
+            .unwrap();
+    }
+}
@@ -2,7 +2,8 @@ use alloy_consensus::Account;
 use alloy_eips::BlockNumberOrTag;
 use alloy_primitives::{Address, Bytes, B256, U256};
 use alloy_rpc_client::{ClientBuilder, ReqwestClient};
-use alloy_rpc_types::EIP1186AccountProofResponse;
+use alloy_rpc_types::{EIP1186AccountProofResponse, EIP1186StorageProof};
+use alloy_transport::{TransportError, TransportErrorKind};
 use url::Url;
 
 /// A provider for accessing account and storage proofs
@@ -70,6 +71,41 @@ impl AccountProvider {
         Ok((convert, response.account_proof))
     }
 
+    /// Fetches the bytecode of a given Ethereum address at a specified block.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use alloy_primitives::Address;
+    /// use hdp_lib::*;
+    /// use std::str::FromStr;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let provider = AccountProvider::new(Url::parse("https://YOUR_RPC_URL").unwrap());
+    ///     let address = Address::from_str("0x75cec1db9dceb703200eaa6595f66885c962b920").unwrap();
+    ///     let block_number = 5641516;
+    ///
+    ///     match provider.get_code(address, block_number).await {
+    ///         Ok(code) => println!("Code: {:?}", code),
+    ///         Err(e) => eprintln!("Error fetching code: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn get_code(
+        &self,
+        address: Address,
+        block_number: u64,
+    ) -> Result<Bytes, alloy_transport::TransportError> {
+        let mut batch = self.client.new_batch();
+        let code_fut: alloy_rpc_client::Waiter<Bytes> = batch.add_call(
+            "eth_getCode",
+            &(address, BlockNumberOrTag::from(block_number)),
+        )?;
+        batch.send().await?;
+        code_fut.await
+    }
+
     /// Fetches storage data for a given Ethereum address and storage slot at a specific block.
     ///
     /// # Examples
@@ -130,13 +166,76 @@ impl AccountProvider {
             storage_value,
         ))
     }
+
+    /// Fetches storage data for several storage slots of a given Ethereum address at a specific block,
+    /// with a single `eth_getProof` request.
+    ///
+    /// The account proof is shared by all slots. Storage proofs and values are matched to
+    /// `storage_slots` by their key and returned in that order.
+    pub async fn get_storages(
+        &self,
+        address: Address,
+        block_number: u64,
+        storage_slots: &[B256],
+    ) -> Result<(Account, Vec<Bytes>, Vec<(Vec<Bytes>, U256)>), alloy_transport::TransportError>
+    {
+        let mut batch = self.client.new_batch();
+        let block_header_fut: alloy_rpc_client::Waiter<EIP1186AccountProofResponse> = batch
+            .add_call(
+                "eth_getProof",
+                &(
+                    address,
+                    storage_slots.to_vec(),
+                    BlockNumberOrTag::from(block_number),
+                ),
+            )?;
+        batch.send().await?;
+        let response: EIP1186AccountProofResponse = block_header_fut.await?;
+        let converted_account: Account = Account {
+            nonce: response.nonce,
+            balance: response.balance,
+            code_hash: response.code_hash,
+            storage_root: response.storage_hash,
+        };
+        let storages = match_storage_proofs(storage_slots, response.storage_proof)?;
+        Ok((converted_account, response.account_proof, storages))
+    }
+}
+
+/// Matches the storage proofs of an `eth_getProof` response to the requested `storage_slots` by
+/// their key, returning their proofs and values in the order of `storage_slots`.
+fn match_storage_proofs(
+    storage_slots: &[B256],
+    storage_proofs: Vec<EIP1186StorageProof>,
+) -> Result<Vec<(Vec<Bytes>, U256)>, TransportError> {
+    if storage_proofs.len() != storage_slots.len() {
+        return Err(TransportErrorKind::custom_str(&format!(
+            "eth_getProof returned {} storage proofs for {} slots",
+            storage_proofs.len(),
+            storage_slots.len()
+        )));
+    }
+    storage_slots
+        .iter()
+        .map(|storage_slot| {
+            storage_proofs
+                .iter()
+                .find(|storage| storage.key == (*storage_slot).into())
+                .map(|storage| (storage.proof.clone(), storage.value))
+                .ok_or_else(|| {
+                    TransportErrorKind::custom_str(&format!(
+                        "eth_getProof returned no proof of storage slot {storage_slot}"
+                    ))
+                })
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{chain::ChainId, header::IndexerClient, mpt::Mpt, utils::get_rpc_urls};
     use alloy_consensus::Header;
-    use alloy_primitives::U256;
+    use alloy_primitives::{keccak256, U256};
     use std::str::FromStr;
 
     use super::*;
@@ -167,6 +266,19 @@ mod tests {
         mpt.verify_account(proof, account, target_account).unwrap();
     }
 
+    #[tokio::test]
+    async fn test_get_code() {
+        let chain_map = get_rpc_urls();
+        let url = chain_map.get(&ChainId::EthereumSepolia).unwrap().to_owned();
+        let provider = AccountProvider::new(url);
+        let target_account =
+            Address::from_str("0x75cec1db9dceb703200eaa6595f66885c962b920").unwrap();
+        let (account, _proof) = provider.get_account(target_account, 5641516).await.unwrap();
+        let code = provider.get_code(target_account, 5641516).await.unwrap();
+
+        assert_eq!(keccak256(&code), account.code_hash);
+    }
+
     #[tokio::test]
     async fn test_get_storage() {
         let chain_map = get_rpc_urls();
@@ -189,4 +301,63 @@ mod tests {
         mpt.verify_storage(storage_proof, storage_key, storage_value)
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn test_get_storages() {
+        let chain_map = get_rpc_urls();
+        let url = chain_map.get(&ChainId::EthereumSepolia).unwrap().to_owned();
+        let provider = AccountProvider::new(url);
+        let storage_slots: Vec<B256> = (0..4).map(|i| U256::from(i).into()).collect();
+        let (account, _account_proof, storages) = provider
+            .get_storages(
+                Address::from_str("0x75cec1db9dceb703200eaa6595f66885c962b920").unwrap(),
+                5641516,
+                &storage_slots,
+            )
+            .await
+            .unwrap();
+        assert_eq!(storages.len(), storage_slots.len());
+
+        // Every storage proof shares the same storage root
+        let mpt = Mpt {
+            root: account.storage_root,
+        };
+        for (storage_slot, (storage_proof, storage_value)) in
+            storage_slots.into_iter().zip(storages)
+        {
+            mpt.verify_storage(storage_proof, storage_slot, storage_value)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_match_storage_proofs() {
+        let storage_slots: Vec<B256> = (0..3).map(|i| U256::from(i).into()).collect();
+        let storage_proof = |i: u64| EIP1186StorageProof {
+            key: B256::from(U256::from(i)).into(),
+            value: U256::from(i + 10),
+            proof: vec![Bytes::from(vec![i as u8])],
+        };
+
+        // Proofs served out of order are matched by their key
+        let storages = match_storage_proofs(
+            &storage_slots,
+            vec![storage_proof(2), storage_proof(0), storage_proof(1)],
+        )
+        .unwrap();
+        for (i, (proof, value)) in storages.into_iter().enumerate() {
+            assert_eq!(proof, vec![Bytes::from(vec![i as u8])]);
+            assert_eq!(value, U256::from(i + 10));
+        }
+
+        // A missing proof, or a proof of another slot, is rejected
+        assert!(
+            match_storage_proofs(&storage_slots, vec![storage_proof(0), storage_proof(1)]).is_err()
+        );
+        assert!(match_storage_proofs(
+            &storage_slots,
+            vec![storage_proof(0), storage_proof(1), storage_proof(3)]
+        )
+        .is_err());
+    }
 }
@@ -0,0 +1,437 @@
+use crate::{
+    chain::ChainId,
+    memorizer::{
+        keys::{HeaderKey, MemorizerKey},
+        values::{HeaderMemorizerValue, MemorizerValue},
+        Memorizer,
+    },
+    mmr::{Mmr, MmrConsistencyProof, MmrError, MmrMeta},
+    provider::{
+        block::{BlockProvider, RAW_HEADERS_BATCH_SIZE},
+        header::{HeaderRpc, IndexerRpc, RlpBlockHeader},
+    },
+};
+use alloy_consensus::Header;
+use alloy_primitives::{hex, keccak256, Bytes, B256, U256};
+use alloy_rlp::Decodable;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror_no_std::Error;
+use url::Url;
+
+/// Defines errors that may occur while maintaining a [`LocalAccumulator`].
+#[derive(Debug, Error)]
+pub enum AccumulatorError {
+    /// Represents an error reading or writing the accumulator file.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// Represents an error encoding or decoding the accumulator file.
+    #[error(transparent)]
+    Bincode(#[from] bincode::Error),
+
+    /// Represents an error decoding an RLP-encoded header.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+
+    /// Represents an error decoding a hex-encoded header.
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+
+    /// Represents an error in the MMR.
+    #[error(transparent)]
+    Mmr(#[from] MmrError),
+
+    /// Represents an error in fetching headers from the node.
+    #[error(transparent)]
+    Transport(#[from] alloy_transport::TransportError),
+
+    /// Indicates that a header does not follow the last accumulated header.
+    #[error("Expected header of block {expected}, got block {got}")]
+    NonConsecutiveHeader {
+        /// Number of the next block to accumulate.
+        expected: u64,
+        /// Number of the block of the header.
+        got: u64,
+    },
+
+    /// Indicates that a header does not point to the last accumulated header.
+    #[error("Parent hash of block {0} does not match the accumulated header")]
+    ParentHashMismatch(u64),
+
+    /// Indicates that a block is not in the accumulator.
+    #[error("Header of block {0} is not accumulated")]
+    MissingHeader(u64),
+}
+
+/// The content of the accumulator file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AccumulatorState {
+    mmr_id: U256,
+    /// Number of the first accumulated block.
+    first_block: u64,
+    /// RLP-encoded headers, from `first_block` on.
+    headers: Vec<Bytes>,
+    /// Element index of each header in the MMR.
+    element_indices: Vec<u128>,
+    mmr: Mmr,
+}
+
+/// A keccak header MMR maintained locally and kept on disk, for chains without a
+/// Herodotus accumulator such as devnets and private chains.
+///
+/// Headers are appended one block after the other, each pointing to the previous one.
+/// Appended headers are only kept in memory until [`LocalAccumulator::save`] is called.
+#[derive(Debug)]
+pub struct LocalAccumulator {
+    path: PathBuf,
+    state: AccumulatorState,
+}
+
+impl LocalAccumulator {
+    /// Opens the accumulator stored at `path`, or creates an empty one identified by
+    /// `mmr_id` if the file does not exist.
+    ///
+    /// The `mmr_id` of an existing accumulator is kept.
+    pub fn open<P: AsRef<Path>>(path: P, mmr_id: U256) -> Result<Self, AccumulatorError> {
+        let path = path.as_ref().to_path_buf();
+        let state = if path.exists() {
+            bincode::deserialize(&fs::read(&path)?)?
+        } else {
+            AccumulatorState {
+                mmr_id,
+                ..Default::default()
+            }
+        };
+        Ok(Self { path, state })
+    }
+
+    /// Writes the accumulator to its file.
+    pub fn save(&self) -> Result<(), AccumulatorError> {
+        // Write to a temporary file first, so that an interrupted save keeps the previous state.
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, bincode::serialize(&self.state)?)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Returns the ID of the accumulated MMR.
+    pub fn mmr_id(&self) -> U256 {
+        self.state.mmr_id
+    }
+
+    /// Returns the current size of the MMR.
+    pub fn mmr_size(&self) -> u64 {
+        self.state.mmr.size()
+    }
+
+    /// Returns the number of the next block to accumulate.
+    pub fn next_block_number(&self) -> u64 {
+        self.state.first_block + self.state.headers.len() as u64
+    }
+
+    /// Appends an RLP-encoded header, returning its element index in the MMR.
+    ///
+    /// The first header can be any block, while every later header must be the child
+    /// of the last accumulated header.
+    pub fn append_rlp(&mut self, rlp: &[u8]) -> Result<u128, AccumulatorError> {
+        let header = Header::decode(&mut &rlp[..])?;
+        if let Some(last) = self.state.headers.last() {
+            let expected = self.next_block_number();
+            if header.number != expected {
+                return Err(AccumulatorError::NonConsecutiveHeader {
+                    expected,
+                    got: header.number,
+                });
+            }
+            if header.parent_hash != keccak256(last) {
+                return Err(AccumulatorError::ParentHashMismatch(header.number));
+            }
+        } else {
+            self.state.first_block = header.number;
+        }
+
+        let element_index = self.state.mmr.append(keccak256(rlp));
+        self.state.headers.push(Bytes::copy_from_slice(rlp));
+        self.state.element_indices.push(element_index);
+        Ok(element_index)
+    }
+
+    /// Appends the headers of a file holding one hex-encoded RLP header per line,
+    /// returning the number of appended headers.
+    pub fn import_file<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, AccumulatorError> {
+        let content = fs::read_to_string(path)?;
+        let mut count = 0;
+        for line in content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            self.append_rlp(&hex::decode(line)?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Appends the headers of the node at `rpc_url`, from the next block to accumulate up to
+    /// `to_block`, returning the number of appended headers.
+    ///
+    /// Raw headers are fetched with [`BlockProvider::get_raw_headers`], which requires the
+    /// `debug` namespace of the node, and every batch is appended before the next one is fetched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use alloy_primitives::U256;
+    /// use url::Url;
+    /// use hdp_lib::accumulator::LocalAccumulator;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut accumulator = LocalAccumulator::open("accumulator.bin", U256::from(1)).unwrap();
+    ///     let url = Url::parse("http://localhost:8545").unwrap();
+    ///     match accumulator.sync(url, 100).await {
+    ///         Ok(count) => println!("{count} headers appended"),
+    ///         Err(e) => eprintln!("Error syncing headers: {:?}", e),
+    ///     }
+    ///     accumulator.save().unwrap();
+    /// }
+    /// ```
+    pub async fn sync(&mut self, rpc_url: Url, to_block: u64) -> Result<usize, AccumulatorError> {
+        let provider = BlockProvider::new(rpc_url);
+        let mut count = 0;
+        while self.next_block_number() <= to_block {
+            let from_block = self.next_block_number();
+            let last_block = to_block.min(from_block + RAW_HEADERS_BATCH_SIZE - 1);
+
+            for rlp in provider.get_raw_headers(from_block..=last_block).await? {
+                self.append_rlp(&rlp)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Returns the peaks of the MMR when it had `mmr_size` elements.
+    pub fn peaks_at(&self, mmr_size: u64) -> Result<Vec<B256>, AccumulatorError> {
+        Ok(self.state.mmr.peaks_at(mmr_size)?)
+    }
+
+    /// Returns the root of the MMR when it had `mmr_size` elements.
+    pub fn root_at(&self, mmr_size: u64) -> Result<B256, AccumulatorError> {
+        Ok(self.state.mmr.root_at(mmr_size)?)
+    }
+
+    /// Returns the metadata of the MMR when it had `mmr_size` elements, under the local `mmr_id`.
+    pub fn meta_at(&self, mmr_size: u64) -> Result<MmrMeta, AccumulatorError> {
+        Ok(self.state.mmr.meta_at(self.state.mmr_id, mmr_size)?)
+    }
+
+    /// Returns the proof that the MMR when it had `old_size` elements is a prefix of the
+    /// current MMR.
+    pub fn consistency_proof(
+        &self,
+        old_size: u64,
+    ) -> Result<MmrConsistencyProof, AccumulatorError> {
+        Ok(self
+            .state
+            .mmr
+            .consistency_proof(old_size, self.mmr_size())?)
+    }
+
+    /// Returns the header of `block_number` with its inclusion proof against the MMR
+    /// when it had `mmr_size` elements.
+    pub fn header_value_at(
+        &self,
+        block_number: u64,
+        mmr_size: u64,
+    ) -> Result<HeaderMemorizerValue, AccumulatorError> {
+        let position = block_number
+            .checked_sub(self.state.first_block)
+            .filter(|position| *position < self.state.headers.len() as u64)
+            .ok_or(AccumulatorError::MissingHeader(block_number))? as usize;
+        let rlp = &self.state.headers[position];
+        let element_index = self.state.element_indices[position];
+
+        Ok(HeaderMemorizerValue {
+            header: Header::decode(&mut rlp.as_ref())?,
+            element_index,
+            element_hash: keccak256(rlp),
+            rlp: hex::encode(rlp),
+            proof: self.state.mmr.proof_at(element_index, mmr_size)?,
+        })
+    }
+
+    /// Returns the headers of `block_numbers` with their inclusion proofs against the
+    /// current MMR, in the shape served by the Indexer.
+    pub fn indexer_rpc(&self, block_numbers: &[u64]) -> Result<IndexerRpc, AccumulatorError> {
+        let mmr_size = self.mmr_size();
+        let proofs = block_numbers
+            .iter()
+            .map(|block_number| {
+                let header_value = self.header_value_at(*block_number, mmr_size)?;
+                Ok(HeaderRpc {
+                    element_index: header_value.element_index,
+                    element_hash: header_value.element_hash,
+                    block_number: u128::from(*block_number),
+                    rlp_block_header: RlpBlockHeader {
+                        string: header_value.rlp,
+                    },
+                    siblings_hashes: header_value.proof,
+                })
+            })
+            .collect::<Result<_, AccumulatorError>>()?;
+
+        Ok(IndexerRpc {
+            meta: (&self.meta_at(mmr_size)?).into(),
+            proofs,
+        })
+    }
+
+    /// Memorizes the headers of `block_numbers` together with the current MMR metadata,
+    /// so that they are verified against the local accumulator in zkVM mode.
+    pub fn memorize(
+        &self,
+        memorizer: &mut Memorizer,
+        chain_id: ChainId,
+        block_numbers: &[u64],
+    ) -> Result<(), AccumulatorError> {
+        let mmr_size = self.mmr_size();
+        for block_number in block_numbers {
+            let header_value = self.header_value_at(*block_number, mmr_size)?;
+            let header_key: MemorizerKey = HeaderKey {
+                chain_id,
+                block_number: *block_number,
+            }
+            .into();
+            memorizer
+                .map
+                .insert(header_key, (MemorizerValue::Header(header_value), false));
+        }
+        memorizer.mmr_meta.insert(chain_id, self.meta_at(mmr_size)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempdir::TempDir;
+
+    /// Encodes a chain of `count` headers starting at `first_block`.
+    fn header_chain(first_block: u64, count: u64) -> Vec<Vec<u8>> {
+        let mut parent_hash = B256::ZERO;
+        (first_block..first_block + count)
+            .map(|number| {
+                let header = Header {
+                    number,
+                    parent_hash,
+                    timestamp: 1_700_000_000 + number * 12,
+                    ..Default::default()
+                };
+                parent_hash = header.hash_slow();
+                alloy_rlp::encode(&header)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_local_accumulator() {
+        let binding = TempDir::new("accumulator").unwrap();
+        let path = binding.path().join("accumulator.bin");
+        let headers = header_chain(1000, 12);
+
+        let mut accumulator = LocalAccumulator::open(&path, U256::from(42)).unwrap();
+        for rlp in &headers[..5] {
+            accumulator.append_rlp(rlp).unwrap();
+        }
+        let early_size = accumulator.mmr_size();
+        for rlp in &headers[5..] {
+            accumulator.append_rlp(rlp).unwrap();
+        }
+        accumulator.save().unwrap();
+
+        // Reopening keeps the state and the original ID
+        let accumulator = LocalAccumulator::open(&path, U256::from(7)).unwrap();
+        assert_eq!(accumulator.mmr_id(), U256::from(42));
+        assert_eq!(accumulator.next_block_number(), 1012);
+
+        // Headers prove against the current and earlier snapshots
+        for mmr_size in [early_size, accumulator.mmr_size()] {
+            let meta = accumulator.meta_at(mmr_size).unwrap();
+            assert_eq!(meta.root_hash, accumulator.root_at(mmr_size).unwrap());
+            let header_value = accumulator.header_value_at(1003, mmr_size).unwrap();
+            assert_eq!(header_value.header.number, 1003);
+            assert!(meta
+                .verify_proof(
+                    header_value.element_index,
+                    header_value.element_hash,
+                    header_value.proof,
+                )
+                .unwrap());
+        }
+        // The earlier snapshot is a prefix of the current one
+        let proof = accumulator.consistency_proof(early_size).unwrap();
+        assert!(accumulator
+            .meta_at(early_size)
+            .unwrap()
+            .verify_consistency(
+                &accumulator.meta_at(accumulator.mmr_size()).unwrap(),
+                &proof
+            )
+            .unwrap());
+        assert!(matches!(
+            accumulator.header_value_at(1008, early_size),
+            Err(AccumulatorError::Mmr(_))
+        ));
+        assert!(matches!(
+            accumulator.header_value_at(999, early_size),
+            Err(AccumulatorError::MissingHeader(999))
+        ));
+
+        // The Indexer shape converts back into the same metadata
+        let indexer_rpc = accumulator.indexer_rpc(&[1000, 1011]).unwrap();
+        assert_eq!(indexer_rpc.proofs.len(), 2);
+        let meta: MmrMeta = indexer_rpc.meta.into();
+        assert_eq!(meta, accumulator.meta_at(accumulator.mmr_size()).unwrap());
+        let header: Header = indexer_rpc.proofs[1].rlp_block_header.clone().into();
+        assert_eq!(header.number, 1011);
+
+        let mut memorizer = Memorizer::new(HashMap::default(), "ETHEREUM_SEPOLIA");
+        accumulator
+            .memorize(&mut memorizer, ChainId::EthereumSepolia, &[1000, 1011])
+            .unwrap();
+        assert_eq!(memorizer.map.len(), 2);
+        assert_eq!(
+            memorizer.mmr_meta[&ChainId::EthereumSepolia].mmr_id,
+            U256::from(42)
+        );
+    }
+
+    #[test]
+    fn test_append_non_consecutive_header() {
+        let binding = TempDir::new("accumulator").unwrap();
+        let path = binding.path().join("accumulator.bin");
+        let headers = header_chain(0, 3);
+        let other_headers = header_chain(1, 2);
+
+        let mut accumulator = LocalAccumulator::open(path, U256::from(1)).unwrap();
+        accumulator.append_rlp(&headers[0]).unwrap();
+        assert!(matches!(
+            accumulator.append_rlp(&headers[2]),
+            Err(AccumulatorError::NonConsecutiveHeader {
+                expected: 1,
+                got: 2
+            })
+        ));
+        assert!(matches!(
+            accumulator.append_rlp(&other_headers[0]),
+            Err(AccumulatorError::ParentHashMismatch(1))
+        ));
+        accumulator.append_rlp(&headers[1]).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+use alloy_eips::BlockNumberOrTag;
+use alloy_primitives::{Bytes, U64};
+use alloy_rpc_client::{ClientBuilder, ReqwestClient};
+use serde::Deserialize;
+use std::ops::RangeInclusive;
+use url::Url;
+
+/// Maximum number of headers requested in a single RPC batch.
+pub(crate) const RAW_HEADERS_BATCH_SIZE: u64 = 100;
+
+/// The subset of an `eth_getBlockByNumber` response that carries the block timestamp.
+#[derive(Deserialize, Debug)]
+struct BlockTimestampRpc {
+    timestamp: U64,
+}
+
+/// A provider for searching blocks by their properties.
+#[derive(Debug)]
+pub struct BlockProvider {
+    /// The RPC client.
+    pub client: ReqwestClient,
+}
+
+impl BlockProvider {
+    /// Creates a new [`BlockProvider`] instance with the given RPC URL
+    pub fn new(rpc_url: Url) -> Self {
+        Self {
+            client: ClientBuilder::default().http(rpc_url),
+        }
+    }
+
+    /// Fetches the number of the most recent block.
+    pub async fn get_latest_block_number(&self) -> Result<u64, alloy_transport::TransportError> {
+        let mut batch = self.client.new_batch();
+        let number_fut: alloy_rpc_client::Waiter<U64> = batch.add_call("eth_blockNumber", &())?;
+        batch.send().await?;
+        Ok(number_fut.await?.to())
+    }
+
+    /// Fetches the timestamp of the specified block.
+    pub async fn get_block_timestamp(
+        &self,
+        block_number: u64,
+    ) -> Result<u64, alloy_transport::TransportError> {
+        let mut batch = self.client.new_batch();
+        let block_fut: alloy_rpc_client::Waiter<BlockTimestampRpc> = batch.add_call(
+            "eth_getBlockByNumber",
+            &(BlockNumberOrTag::from(block_number), false),
+        )?;
+        batch.send().await?;
+        Ok(block_fut.await?.timestamp.to())
+    }
+
+    /// Fetches the RLP-encoded headers of a range of blocks, in batches of at most
+    /// 100 `debug_getRawHeader` calls.
+    pub async fn get_raw_headers(
+        &self,
+        block_numbers: RangeInclusive<u64>,
+    ) -> Result<Vec<Bytes>, alloy_transport::TransportError> {
+        let (mut from_block, to_block) = block_numbers.into_inner();
+        let mut headers = Vec::new();
+        while from_block <= to_block {
+            let last_block = to_block.min(from_block + RAW_HEADERS_BATCH_SIZE - 1);
+
+            let mut batch = self.client.new_batch();
+            let header_futs: Vec<alloy_rpc_client::Waiter<Bytes>> = (from_block..=last_block)
+                .map(|block_number| {
+                    batch.add_call(
+                        "debug_getRawHeader",
+                        &(BlockNumberOrTag::from(block_number),),
+                    )
+                })
+                .collect::<Result<_, _>>()?;
+            batch.send().await?;
+
+            for header_fut in header_futs {
+                headers.push(header_fut.await?);
+            }
+            from_block = last_block + 1;
+        }
+        Ok(headers)
+    }
+
+    /// Binary-searches the block `N` such that `timestamp(N) <= timestamp < timestamp(N + 1)`.
+    ///
+    /// Returns `None` if the timestamp precedes the genesis block, or if block `N + 1`
+    /// has not been produced yet.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::Url;
+    /// use hdp_lib::BlockProvider;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let provider = BlockProvider::new(Url::parse("https://YOUR_RPC_URL").unwrap());
+    ///     match provider.find_block_by_timestamp(1708000000).await {
+    ///         Ok(block_number) => println!("{:?}", block_number),
+    ///         Err(e) => eprintln!("Error searching block: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn find_block_by_timestamp(
+        &self,
+        timestamp: u64,
+    ) -> Result<Option<u64>, alloy_transport::TransportError> {
+        let mut low = 0;
+        let mut high = self.get_latest_block_number().await?;
+        if self.get_block_timestamp(low).await? > timestamp
+            || self.get_block_timestamp(high).await? <= timestamp
+        {
+            return Ok(None);
+        }
+
+        // Invariant: timestamp(low) <= timestamp < timestamp(high)
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if self.get_block_timestamp(mid).await? <= timestamp {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(Some(low))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{chain::ChainId, utils::get_rpc_urls};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_find_block_by_timestamp() {
+        let chain_map = get_rpc_urls();
+        let url = chain_map.get(&ChainId::EthereumSepolia).unwrap().to_owned();
+        let provider = BlockProvider::new(url);
+
+        let timestamp = provider.get_block_timestamp(5244652).await.unwrap();
+        let block_number = provider
+            .find_block_by_timestamp(timestamp)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block_number, 5244652);
+
+        let next_timestamp = provider.get_block_timestamp(5244653).await.unwrap();
+        let block_number = provider
+            .find_block_by_timestamp(next_timestamp - 1)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block_number, 5244652);
+    }
+
+    #[tokio::test]
+    async fn test_get_raw_headers() {
+        let chain_map = get_rpc_urls();
+        let url = chain_map.get(&ChainId::EthereumSepolia).unwrap().to_owned();
+        let provider = BlockProvider::new(url);
+
+        let headers = provider.get_raw_headers(5244650..=5244652).await.unwrap();
+        assert_eq!(headers.len(), 3);
+        let headers: Vec<alloy_consensus::Header> = headers
+            .iter()
+            .map(|rlp| alloy_rlp::Decodable::decode(&mut rlp.as_ref()).unwrap())
+            .collect();
+        assert_eq!(headers[0].number, 5244650);
+        assert_eq!(headers[2].parent_hash, headers[1].hash_slow());
+    }
+}
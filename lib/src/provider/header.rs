@@ -1,16 +1,68 @@
-use crate::{chain::ChainId, mmr::MmrMeta};
+use crate::{
+    chain::ChainId,
+    mmr::{
+        element_index_to_leaf_index, KeccakHasher, MmrConsistencyProof, MmrError, MmrHasher,
+        MmrMeta,
+    },
+};
 use alloy_consensus::Header;
 use alloy_primitives::{
     hex::{self, FromHex},
     B256,
 };
 use alloy_rlp::Decodable;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{collections::HashSet, marker::PhantomData};
+use thiserror_no_std::Error;
 
 /// Indexer RPC
 /// Detail documentation: https://rs-indexer.api.herodotus.cloud/swagger/#/accumulators/get_proofs
 const INDEXER_RPC_URL: &str = "https://rs-indexer.api.herodotus.cloud/accumulators/proofs";
 
+/// Environment variable overriding [`INDEXER_RPC_URL`], e.g. to use a local indexer.
+const INDEXER_RPC_URL_ENV: &str = "INDEXER_RPC_URL";
+
+/// Contract address reported for MMRs that are not deployed on chain.
+const NO_CONTRACT_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
+
+/// Maximum number of block numbers sent in a single Indexer request.
+const INDEXER_BATCH_SIZE: usize = 50;
+
+/// Number of attempts to collect a batch of headers under a single MMR snapshot.
+const MAX_SNAPSHOT_ATTEMPTS: usize = 3;
+
+/// Defines errors that may occur when fetching headers in batch from the Indexer.
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    /// Represents an error in HTTP requests.
+    #[error(transparent)]
+    ReqwestError(#[from] reqwest::Error),
+
+    /// Indicates that no block number was requested.
+    #[error("No block number requested")]
+    EmptyRequest,
+
+    /// Indicates that the Indexer did not return a requested block header.
+    #[error("Header of block {0} is missing from the Indexer response")]
+    MissingHeader(u64),
+
+    /// Indicates that the MMR kept growing while the batch was fetched.
+    #[error("Headers could not be collected under a single MMR snapshot")]
+    InconsistentSnapshot,
+
+    /// Indicates consecutive blocks that are not accumulated in consecutive MMR leaves.
+    #[error("Blocks are not accumulated in consecutive MMR leaves")]
+    InvalidLeafOrder,
+
+    /// Indicates that the Indexer served a snapshot of another MMR.
+    #[error("MMR snapshot belongs to another MMR")]
+    MmrIdMismatch,
+
+    /// Indicates a failure in MMR proof verification.
+    #[error(transparent)]
+    MmrError(#[from] MmrError),
+}
+
 /// The response structure from the Indexer API.
 /// Contains a list of [`IndexerRpc`] entries.
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,6 +70,13 @@ pub struct IndexerResponse {
     data: Vec<IndexerRpc>,
 }
 
+impl IndexerResponse {
+    /// Creates a new [`IndexerResponse`] from its entries.
+    pub fn new(data: Vec<IndexerRpc>) -> Self {
+        Self { data }
+    }
+}
+
 /// Represents a complete response for a block from the Indexer,
 /// containing MMR metadata and a list of header proofs.
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -28,8 +87,36 @@ pub struct IndexerRpc {
     pub proofs: Vec<HeaderRpc>,
 }
 
+/// Header proofs from the Indexer, with the metadata of the MMR hashed with `H` they prove against.
+#[derive(Debug)]
+pub struct HeaderProofs<H: MmrHasher = KeccakHasher> {
+    /// Metadata of the MMR snapshot.
+    pub meta: MmrMeta<H>,
+    /// List of header proofs.
+    pub proofs: Vec<HeaderRpc>,
+}
+
+impl<H: MmrHasher> From<IndexerRpc> for HeaderProofs<H> {
+    fn from(indexer_rpc: IndexerRpc) -> Self {
+        Self {
+            meta: indexer_rpc.meta.into(),
+            proofs: indexer_rpc.proofs,
+        }
+    }
+}
+
+/// The current MMR snapshot of the Indexer, with the proof that an older snapshot of the same
+/// MMR is a prefix of it.
+#[derive(Debug)]
+pub struct SnapshotConsistency<H: MmrHasher = KeccakHasher> {
+    /// Metadata of the newer MMR snapshot.
+    pub meta: MmrMeta<H>,
+    /// Proof that the older snapshot is a prefix of the newer one.
+    pub proof: MmrConsistencyProof,
+}
+
 /// Metadata from the MMR related to a specific block, as provided by the Indexer API.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct MmrRpc {
     mmr_size: u128,
     mmr_id: String,
@@ -45,15 +132,37 @@ pub struct HeaderRpc {
     /// The element index of the block header in the MMR.
     pub element_index: u128,
     /// The hash of the block header element.
+    #[serde(deserialize_with = "deserialize_hash")]
     pub element_hash: B256,
     /// The block number.
     pub block_number: u128,
     /// The RLP-encoded block header.
     pub rlp_block_header: RlpBlockHeader,
     /// Merkle Mountain Range inclusion proof.
+    #[serde(deserialize_with = "deserialize_hashes")]
     pub siblings_hashes: Vec<B256>,
 }
 
+/// Parses a hash returned by the Indexer.
+///
+/// Poseidon hashes are field elements, whose hex strings drop their leading zeros.
+fn parse_hash(hash: &str) -> Result<B256, hex::FromHexError> {
+    let digits = hash.strip_prefix("0x").unwrap_or(hash);
+    B256::from_hex(format!("{digits:0>64}"))
+}
+
+fn deserialize_hash<'de, D: Deserializer<'de>>(deserializer: D) -> Result<B256, D::Error> {
+    let hash = String::deserialize(deserializer)?;
+    parse_hash(&hash).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_hashes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<B256>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|hash| parse_hash(hash).map_err(serde::de::Error::custom))
+        .collect()
+}
+
 /// Encapsulates an RLP-encoded block header as a single hex string.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "PascalCase")]
@@ -72,37 +181,56 @@ impl From<RlpBlockHeader> for Header {
     }
 }
 
-impl From<MmrRpc> for MmrMeta {
+impl<H: MmrHasher> From<MmrRpc> for MmrMeta<H> {
     fn from(mmr_rpc: MmrRpc) -> Self {
         let mmr_peaks: Vec<B256> = mmr_rpc
             .mmr_peaks
             .into_iter()
-            .map(|peak| B256::from_hex(peak).expect("Failed to parse hex"))
+            .map(|peak| parse_hash(&peak).expect("Failed to parse hex"))
             .collect();
         Self::new(
             mmr_rpc.mmr_id,
-            B256::from_hex(mmr_rpc.mmr_root).expect("Failed to parse hex"),
+            parse_hash(&mmr_rpc.mmr_root).expect("Failed to parse hex"),
             mmr_rpc.mmr_size,
             mmr_peaks,
         )
     }
 }
 
+/// Converts the metadata of a locally built MMR, which has no contract address.
+impl<H: MmrHasher> From<&MmrMeta<H>> for MmrRpc {
+    fn from(mmr_meta: &MmrMeta<H>) -> Self {
+        Self {
+            mmr_size: mmr_meta.mmr_size.to::<u128>(),
+            mmr_id: format!("{:#x}", mmr_meta.mmr_id),
+            mmr_root: mmr_meta.root_hash.to_string(),
+            mmr_peaks: mmr_meta.peaks().iter().map(B256::to_string).collect(),
+            contract_address: NO_CONTRACT_ADDRESS.to_string(),
+        }
+    }
+}
+
 /// Client for interacting with the Indexer API to fetch block headers
 /// and MMR-related data.
+///
+/// Proofs are requested for the MMR hashed with `H`, keccak by default.
 #[derive(Debug)]
-pub struct IndexerClient {
+pub struct IndexerClient<H: MmrHasher = KeccakHasher> {
     client: reqwest::Client,
+    url: String,
     deployed_on_chain: u128,
     accumulates_chain: u128,
+    hasher: PhantomData<H>,
 }
 
 impl Default for IndexerClient {
     fn default() -> Self {
         Self {
             client: reqwest::Client::new(),
+            url: indexer_rpc_url(),
             deployed_on_chain: 11155111,
             accumulates_chain: 11155111,
+            hasher: PhantomData,
         }
     }
 }
@@ -112,8 +240,29 @@ impl IndexerClient {
     pub fn new(from_chain_id: ChainId, to_chain_id: ChainId) -> Self {
         Self {
             client: reqwest::Client::new(),
+            url: indexer_rpc_url(),
             deployed_on_chain: to_chain_id.to_numeric_id(),
             accumulates_chain: from_chain_id.to_numeric_id(),
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<H: MmrHasher> IndexerClient<H> {
+    /// Sends requests to the Indexer at `url` instead of the hosted one.
+    pub fn with_url<S: Into<String>>(mut self, url: S) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Requests the proofs of the MMR hashed with `G` instead, whose metadata is an [`MmrMeta<G>`].
+    pub fn with_hasher<G: MmrHasher>(self) -> IndexerClient<G> {
+        IndexerClient {
+            client: self.client,
+            url: self.url,
+            deployed_on_chain: self.deployed_on_chain,
+            accumulates_chain: self.accumulates_chain,
+            hasher: PhantomData,
         }
     }
 
@@ -133,20 +282,214 @@ impl IndexerClient {
     ///     }
     /// }
     /// ```
-    pub async fn get_header(&self, block_number: u64) -> Result<IndexerRpc, reqwest::Error> {
-        let url = format!(
-            "{INDEXER_RPC_URL}?deployed_on_chain={}&accumulates_chain={}&hashing_function=keccak&contract_type=AGGREGATOR&block_numbers={}&is_meta_included=true&is_whole_tree=true&is_rlp_included=true",
-            self.deployed_on_chain, self.accumulates_chain, block_number
-        );
-        let res = self.client.get(url).send().await?;
+    pub async fn get_header(&self, block_number: u64) -> Result<HeaderProofs<H>, reqwest::Error> {
+        let res = self
+            .client
+            .get(self.proofs_url(&[block_number]))
+            .send()
+            .await?;
         let indexer_rpc: IndexerResponse = res.json().await?;
-        Ok(indexer_rpc.data.first().expect("Invalid response").clone())
+        Ok(indexer_rpc
+            .data
+            .first()
+            .expect("Invalid response")
+            .clone()
+            .into())
+    }
+
+    /// Fetches the block headers and MMR proofs of many blocks from the Indexer.
+    ///
+    /// Block numbers are sent in chunks, and every proof of the result is taken from the same
+    /// MMR snapshot. If the MMR grows while the chunks are fetched, the whole batch is fetched again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    ///  use hdp_lib::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = IndexerClient::default();
+    ///     let block_numbers: Vec<u64> = (665200..665300).collect();
+    ///     match client.get_headers(&block_numbers).await {
+    ///         Ok(header_rpc) => println!("{:?}", header_rpc),
+    ///         Err(e) => eprintln!("Error fetching headers: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn get_headers(
+        &self,
+        block_numbers: &[u64],
+    ) -> Result<HeaderProofs<H>, IndexerError> {
+        if block_numbers.is_empty() {
+            return Err(IndexerError::EmptyRequest);
+        }
+
+        'attempt: for _ in 0..MAX_SNAPSHOT_ATTEMPTS {
+            let mut meta: Option<MmrRpc> = None;
+            let mut proofs: Vec<HeaderRpc> = Vec::with_capacity(block_numbers.len());
+            for chunk in block_numbers.chunks(INDEXER_BATCH_SIZE) {
+                let res = self.client.get(self.proofs_url(chunk)).send().await?;
+                let indexer_rpc: IndexerResponse = res.json().await?;
+                let Some(chunk_rpc) = indexer_rpc.data.into_iter().next() else {
+                    return Err(IndexerError::MissingHeader(chunk[0]));
+                };
+                match &meta {
+                    Some(meta) if *meta != chunk_rpc.meta => continue 'attempt,
+                    Some(_) => {}
+                    None => meta = Some(chunk_rpc.meta),
+                }
+                proofs.extend(chunk_rpc.proofs);
+            }
+
+            let fetched: HashSet<u128> = proofs.iter().map(|proof| proof.block_number).collect();
+            if let Some(missing) = block_numbers
+                .iter()
+                .find(|block_number| !fetched.contains(&(**block_number as u128)))
+            {
+                return Err(IndexerError::MissingHeader(*missing));
+            }
+
+            return Ok(HeaderProofs {
+                meta: meta.expect("at least one chunk was fetched").into(),
+                proofs,
+            });
+        }
+
+        Err(IndexerError::InconsistentSnapshot)
     }
+
+    /// Fetches the current MMR snapshot from the Indexer, with the proof that the `older`
+    /// snapshot of the same MMR is a prefix of it.
+    ///
+    /// The proof is built from the inclusion proofs of the first leaf of every older mountain
+    /// against the current snapshot. The blocks of these leaves are found from
+    /// `anchor_block_number`, any block of the older snapshot, and the element index of a
+    /// neighbouring block, which tells whether the MMR accumulates blocks forward or backward.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    ///  use hdp_lib::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = IndexerClient::default();
+    ///     let older = client.get_header(665200).await.unwrap().meta;
+    ///     match client.get_consistency_proof(&older, 665200).await {
+    ///         Ok(consistency) => println!("{:?}", consistency),
+    ///         Err(e) => eprintln!("Error fetching consistency proof: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn get_consistency_proof(
+        &self,
+        older: &MmrMeta<H>,
+        anchor_block_number: u64,
+    ) -> Result<SnapshotConsistency<H>, IndexerError> {
+        let old_size = u64::try_from(older.mmr_size).map_err(|_| MmrError::SizeOverflow)?;
+        let (anchor_leaf, step) = self.leaf_order(anchor_block_number).await?;
+        let leaves = MmrConsistencyProof::mountain_leaves(old_size)?;
+        let block_numbers = leaves
+            .iter()
+            .map(|leaf| {
+                let offset = (i128::from(*leaf) - i128::from(anchor_leaf)) * step;
+                u64::try_from(i128::from(anchor_block_number) + offset)
+                    .map_err(|_| IndexerError::InvalidLeafOrder)
+            })
+            .collect::<Result<Vec<u64>, _>>()?;
+
+        let blocks = self.get_headers(&block_numbers).await?;
+        if blocks.meta.mmr_id != older.mmr_id {
+            return Err(IndexerError::MmrIdMismatch);
+        }
+        let proofs = leaves
+            .iter()
+            .zip(&block_numbers)
+            .map(|(leaf, block_number)| {
+                let proof = find_proof(&blocks.proofs, *block_number)?;
+                if leaf_index(proof)? != *leaf {
+                    return Err(IndexerError::InvalidLeafOrder);
+                }
+                Ok(proof.siblings_hashes.clone())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let proof = MmrConsistencyProof::from_inclusion_proofs(old_size, proofs)?;
+        older.verify_consistency(&blocks.meta, &proof)?;
+        Ok(SnapshotConsistency {
+            meta: blocks.meta,
+            proof,
+        })
+    }
+
+    /// Returns the leaf index of `block_number`, and the difference between the block numbers
+    /// of consecutive leaves: 1 if the MMR accumulates blocks forward, -1 if backward.
+    async fn leaf_order(&self, block_number: u64) -> Result<(u64, i128), IndexerError> {
+        // The next block may not be accumulated yet, the previous one is then.
+        let (lower, neighbours) = match self.get_headers(&[block_number, block_number + 1]).await {
+            Err(IndexerError::MissingHeader(_)) if block_number > 0 => (
+                block_number - 1,
+                self.get_headers(&[block_number - 1, block_number]).await?,
+            ),
+            neighbours => (block_number, neighbours?),
+        };
+        let lower_leaf = leaf_index(find_proof(&neighbours.proofs, lower)?)?;
+        let upper_leaf = leaf_index(find_proof(&neighbours.proofs, lower + 1)?)?;
+
+        let step = if upper_leaf == lower_leaf + 1 {
+            1
+        } else if lower_leaf == upper_leaf + 1 {
+            -1
+        } else {
+            return Err(IndexerError::InvalidLeafOrder);
+        };
+        let leaf = if lower == block_number {
+            lower_leaf
+        } else {
+            upper_leaf
+        };
+        Ok((leaf, step))
+    }
+
+    /// Builds the Indexer URL requesting the proofs of `block_numbers`.
+    fn proofs_url(&self, block_numbers: &[u64]) -> String {
+        let block_numbers: String = block_numbers
+            .iter()
+            .map(|block_number| format!("&block_numbers={block_number}"))
+            .collect();
+        format!(
+            "{}?deployed_on_chain={}&accumulates_chain={}&hashing_function={}&contract_type=AGGREGATOR{block_numbers}&is_meta_included=true&is_whole_tree=true&is_rlp_included=true",
+            self.url, self.deployed_on_chain, self.accumulates_chain, H::NAME
+        )
+    }
+}
+
+/// Finds the proof of `block_number` among the proofs served by the Indexer.
+fn find_proof(proofs: &[HeaderRpc], block_number: u64) -> Result<&HeaderRpc, IndexerError> {
+    proofs
+        .iter()
+        .find(|proof| proof.block_number == u128::from(block_number))
+        .ok_or(IndexerError::MissingHeader(block_number))
+}
+
+/// Returns the index of the MMR leaf holding the header of `proof`.
+fn leaf_index(proof: &HeaderRpc) -> Result<u64, IndexerError> {
+    let element_index =
+        u64::try_from(proof.element_index).map_err(|_| MmrError::ElementIndexOverflow)?;
+    Ok(element_index_to_leaf_index(element_index)?)
+}
+
+/// Returns the Indexer URL, taken from the `INDEXER_RPC_URL` environment variable if set.
+fn indexer_rpc_url() -> String {
+    dotenv::dotenv().ok();
+    std::env::var(INDEXER_RPC_URL_ENV).unwrap_or_else(|_| INDEXER_RPC_URL.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mmr::PoseidonHasher;
 
     #[tokio::test]
     async fn test_get_header() {
@@ -161,4 +504,68 @@ mod tests {
             .into();
         println!("{:#?}", header);
     }
+
+    #[tokio::test]
+    async fn test_get_headers() {
+        let client = IndexerClient::default();
+        let block_numbers: Vec<u64> = (665200..665260).collect();
+        let indexer_rpc = client.get_headers(&block_numbers).await.unwrap();
+        assert_eq!(indexer_rpc.proofs.len(), block_numbers.len());
+
+        // Every proof verifies against the single snapshot
+        let mmr = indexer_rpc.meta;
+        for proof in indexer_rpc.proofs {
+            assert!(mmr
+                .verify_proof(
+                    proof.element_index,
+                    proof.element_hash,
+                    proof.siblings_hashes,
+                )
+                .unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_consistency_proof() {
+        let client = IndexerClient::default();
+        let older = client.get_header(665200).await.unwrap().meta;
+        let consistency = client.get_consistency_proof(&older, 665200).await.unwrap();
+        assert_eq!(consistency.meta.mmr_id, older.mmr_id);
+        assert!(consistency.meta.mmr_size >= older.mmr_size);
+        assert!(older
+            .verify_consistency(&consistency.meta, &consistency.proof)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_parse_hash() {
+        let hash = B256::from(alloy_primitives::U256::from(0xabcdef_u64));
+        assert_eq!(parse_hash("0xabcdef").unwrap(), hash);
+        assert_eq!(parse_hash(&hash.to_string()).unwrap(), hash);
+        assert!(parse_hash(&format!("0x1{}", "0".repeat(64))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_header_poseidon() {
+        let client = IndexerClient::default().with_hasher::<PoseidonHasher>();
+        let indexer_rpc = client.get_header(665200).await.unwrap();
+        let proof = indexer_rpc.proofs.first().unwrap().clone();
+
+        // The proven element is the poseidon leaf of the served header
+        let header: Header = proof.rlp_block_header.clone().into();
+        assert_eq!(header.number, 665200);
+        assert_eq!(
+            PoseidonHasher::hash_leaf(&alloy_rlp::encode(&header)),
+            proof.element_hash
+        );
+
+        let mmr: &MmrMeta<PoseidonHasher> = &indexer_rpc.meta;
+        assert!(mmr
+            .verify_proof(
+                proof.element_index,
+                proof.element_hash,
+                proof.siblings_hashes,
+            )
+            .unwrap());
+    }
 }
@@ -1,8 +1,14 @@
 /// account, storage provider
 pub mod account;
+/// local header accumulator
+pub mod accumulator;
+/// block search provider
+pub mod block;
 /// consensus layer header provider
 pub mod cl_header;
 /// header provider
 pub mod header;
 /// transaction, receipt provider
 pub mod transaction;
+/// withdrawal provider
+pub mod withdrawal;
@@ -1,10 +1,30 @@
-use alloy_primitives::{Bytes, B256};
+use crate::mpt::ordered_trie_with_proof;
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{Bytes, B256, U64};
+use alloy_rpc_client::ClientBuilder;
 use eth_trie_proofs::{
     tx::ConsensusTx, tx_receipt::ConsensusTxReceipt, tx_receipt_trie::TxReceiptsMptHandler,
     tx_trie::TxsMptHandler, EthTrieError,
 };
+use serde::Deserialize;
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, LazyLock, Mutex},
+};
 use url::Url;
 
+/// Number of blocks whose transaction and receipt tries are kept by default.
+const DEFAULT_TRIE_CACHE_CAPACITY: usize = 16;
+
+/// Transaction tries shared by every [`TransactionClient`] created with [`TransactionClient::new`].
+static TX_TRIES: LazyLock<Arc<Mutex<TrieCache<TxsMptHandler>>>> =
+    LazyLock::new(|| Arc::new(Mutex::new(TrieCache::new(DEFAULT_TRIE_CACHE_CAPACITY))));
+
+/// Receipt tries shared by every [`TransactionClient`] created with [`TransactionClient::new`].
+static RECEIPT_TRIES: LazyLock<Arc<Mutex<TrieCache<TxReceiptsMptHandler>>>> =
+    LazyLock::new(|| Arc::new(Mutex::new(TrieCache::new(DEFAULT_TRIE_CACHE_CAPACITY))));
+
 /// Represents a response containing transaction details, including the MPT root,
 /// the transaction data, and the proof elements.
 #[derive(Debug)]
@@ -19,6 +39,18 @@ pub struct TransactionResponse {
     pub proof: Vec<Bytes>,
 }
 
+/// Represents a response containing the number of transactions in a block,
+/// together with the exclusion proof for the first index past the last transaction.
+#[derive(Debug)]
+pub struct TransactionCountResponse {
+    /// The number of transactions in the block.
+    pub count: u64,
+    /// The Merkle Patricia Trie root hash for the transactions.
+    pub mpt_root: B256,
+    /// The exclusion proof for the transaction index equal to `count`.
+    pub exclusion_proof: Vec<Bytes>,
+}
+
 /// Represents a response containing receipt details, including the MPT root,
 /// the transaction receipt data, and the proof elements.
 #[derive(Debug)]
@@ -33,9 +65,73 @@ pub struct ReceiptResponse {
     pub proof: Vec<Bytes>,
 }
 
+/// The subset of an `eth_getTransactionByHash` response that locates a transaction on chain.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct TransactionLocationRpc {
+    block_number: Option<U64>,
+    transaction_index: Option<U64>,
+}
+
+/// A least-recently-used cache of built tries, keyed by RPC URL and block number.
+struct TrieCache<T> {
+    capacity: usize,
+    /// Cached tries, from the least to the most recently used.
+    entries: VecDeque<((Url, u64), Arc<Mutex<T>>)>,
+}
+
+impl<T> TrieCache<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the trie of `key`, marking it as the most recently used.
+    fn get(&mut self, key: &(Url, u64)) -> Option<Arc<Mutex<T>>> {
+        let position = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(position)?;
+        let trie = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(trie)
+    }
+
+    /// Inserts the trie of `key`, evicting the least recently used trie if the cache is full.
+    fn insert(&mut self, key: (Url, u64), trie: Arc<Mutex<T>>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries.retain(|(k, _)| *k != key);
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, trie));
+    }
+}
+
+impl<T> fmt::Debug for TrieCache<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrieCache")
+            .field("capacity", &self.capacity)
+            .field(
+                "blocks",
+                &self.entries.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
 /// A client for fetching transactions and receipts, along with their proofs.
-#[derive(Debug)]
-pub struct TransactionClient {}
+///
+/// The transaction and receipt tries of a block are built once, then kept in a
+/// least-recently-used cache, so that proofs for any index of a recent block are
+/// served without fetching the block again.
+#[derive(Debug, Clone)]
+pub struct TransactionClient {
+    tx_tries: Arc<Mutex<TrieCache<TxsMptHandler>>>,
+    receipt_tries: Arc<Mutex<TrieCache<TxReceiptsMptHandler>>>,
+}
 
 impl Default for TransactionClient {
     fn default() -> Self {
@@ -44,13 +140,82 @@ impl Default for TransactionClient {
 }
 
 impl TransactionClient {
-    /// Creates a new [`TransactionClient`].
+    /// Creates a new [`TransactionClient`], sharing the trie cache of the process.
     pub fn new() -> Self {
-        Self {}
+        Self {
+            tx_tries: TX_TRIES.clone(),
+            receipt_tries: RECEIPT_TRIES.clone(),
+        }
+    }
+
+    /// Creates a new [`TransactionClient`] with its own trie cache, keeping the tries
+    /// of at most `capacity` blocks.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            tx_tries: Arc::new(Mutex::new(TrieCache::new(capacity))),
+            receipt_tries: Arc::new(Mutex::new(TrieCache::new(capacity))),
+        }
+    }
+
+    /// Returns the transaction trie of the specified block, building it on a cache miss.
+    async fn tx_trie(
+        &self,
+        url: Url,
+        block_number: u64,
+    ) -> Result<Arc<Mutex<TxsMptHandler>>, EthTrieError> {
+        let key = (url, block_number);
+        let cached = self
+            .tx_tries
+            .lock()
+            .expect("Failed to lock trie cache")
+            .get(&key);
+        if let Some(trie) = cached {
+            return Ok(trie);
+        }
+
+        let mut txs_mpt_handler = TxsMptHandler::new(key.0.clone())?;
+        txs_mpt_handler
+            .build_tx_tree_from_block(block_number)
+            .await?;
+        let trie = Arc::new(Mutex::new(txs_mpt_handler));
+        self.tx_tries
+            .lock()
+            .expect("Failed to lock trie cache")
+            .insert(key, trie.clone());
+        Ok(trie)
+    }
+
+    /// Returns the receipt trie of the specified block, building it on a cache miss.
+    async fn receipt_trie(
+        &self,
+        url: Url,
+        block_number: u64,
+    ) -> Result<Arc<Mutex<TxReceiptsMptHandler>>, EthTrieError> {
+        let key = (url, block_number);
+        let cached = self
+            .receipt_tries
+            .lock()
+            .expect("Failed to lock trie cache")
+            .get(&key);
+        if let Some(trie) = cached {
+            return Ok(trie);
+        }
+
+        let mut receipt_mpt_handler = TxReceiptsMptHandler::new(key.0.clone())?;
+        receipt_mpt_handler
+            .build_tx_receipts_tree_from_block(block_number)
+            .await?;
+        let trie = Arc::new(Mutex::new(receipt_mpt_handler));
+        self.receipt_tries
+            .lock()
+            .expect("Failed to lock trie cache")
+            .insert(key, trie.clone());
+        Ok(trie)
     }
 
     /// Fetches a transaction from the specified block and transaction index,
-    /// building the transaction Merkle Patricia Trie (MPT) to retrieve the proof and transaction data.
+    /// using the cached transaction Merkle Patricia Trie (MPT) of the block, built on first use,
+    /// to retrieve the proof and transaction data.
     ///
     /// # Examples
     ///
@@ -74,10 +239,8 @@ impl TransactionClient {
         block_number: u64,
         tx_index: u64,
     ) -> Result<TransactionResponse, EthTrieError> {
-        let mut txs_mpt_handler = TxsMptHandler::new(url)?;
-        txs_mpt_handler
-            .build_tx_tree_from_block(block_number)
-            .await?;
+        let trie = self.tx_trie(url, block_number).await?;
+        let mut txs_mpt_handler = trie.lock().expect("Failed to lock trie");
         let proof = txs_mpt_handler.get_proof(tx_index)?;
         let proof: Vec<Bytes> = proof.into_iter().map(Bytes::from).collect();
         let tx = txs_mpt_handler.get_tx(tx_index)?;
@@ -90,8 +253,91 @@ impl TransactionClient {
         Ok(tx_res)
     }
 
+    /// Fetches the number of transactions in the specified block, building the transaction
+    /// Merkle Patricia Trie (MPT) to retrieve the exclusion proof for the index past the last transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::Url;
+    /// use hdp_lib::TransactionClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = TransactionClient::new();
+    ///     let url = Url::parse("https://YOUR_RPC_URL").unwrap();
+    ///     match client.get_transaction_count(url, 5244634).await {
+    ///         Ok(response) => println!("{:?}", response),
+    ///         Err(e) => eprintln!("Error fetching transaction count: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn get_transaction_count(
+        &self,
+        url: Url,
+        block_number: u64,
+    ) -> Result<TransactionCountResponse, EthTrieError> {
+        let trie = self.tx_trie(url, block_number).await?;
+        let txs_mpt_handler = trie.lock().expect("Failed to lock trie");
+        let txs: Vec<Bytes> = txs_mpt_handler
+            .get_elements()?
+            .into_iter()
+            .map(|tx| tx.0.encoded_2718().into())
+            .collect();
+        let count = txs.len() as u64;
+        let (mpt_root, exclusion_proof) = ordered_trie_with_proof(&txs, count);
+        Ok(TransactionCountResponse {
+            count,
+            mpt_root,
+            exclusion_proof,
+        })
+    }
+
+    /// Resolves a transaction hash to the block number and index of the transaction
+    /// using `eth_getTransactionByHash`.
+    ///
+    /// Returns `None` if the transaction is unknown to the node or still pending.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use alloy_primitives::b256;
+    /// use url::Url;
+    /// use hdp_lib::TransactionClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = TransactionClient::new();
+    ///     let url = Url::parse("https://YOUR_RPC_URL").unwrap();
+    ///     let tx_hash = b256!("f21b27c5b4a9bd1b7b8f7fa1bda9bbd8bc1b5bb5ba0a9b4b0d4ba7e0a1b2c3d4");
+    ///     match client.get_transaction_location(url, tx_hash).await {
+    ///         Ok(location) => println!("{:?}", location),
+    ///         Err(e) => eprintln!("Error resolving transaction: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn get_transaction_location(
+        &self,
+        url: Url,
+        tx_hash: B256,
+    ) -> Result<Option<(u64, u64)>, alloy_transport::TransportError> {
+        let client = ClientBuilder::default().http(url);
+        let mut batch = client.new_batch();
+        let tx_fut: alloy_rpc_client::Waiter<Option<TransactionLocationRpc>> =
+            batch.add_call("eth_getTransactionByHash", &(tx_hash,))?;
+        batch.send().await?;
+        let location = tx_fut.await?.and_then(|tx| {
+            Some((
+                tx.block_number?.to::<u64>(),
+                tx.transaction_index?.to::<u64>(),
+            ))
+        });
+        Ok(location)
+    }
+
     /// Fetches a transaction receipt from the specified block and transaction index,
-    /// building the receipt Merkle Patricia Trie (MPT) to retrieve the proof and receipt data.
+    /// using the cached receipt Merkle Patricia Trie (MPT) of the block, built on first use,
+    /// to retrieve the proof and receipt data.
     ///
     /// # Examples
     ///
@@ -115,10 +361,8 @@ impl TransactionClient {
         block_number: u64,
         tx_index: u64,
     ) -> Result<ReceiptResponse, EthTrieError> {
-        let mut receipt_mpt_handler = TxReceiptsMptHandler::new(url)?;
-        receipt_mpt_handler
-            .build_tx_receipts_tree_from_block(block_number)
-            .await?;
+        let trie = self.receipt_trie(url, block_number).await?;
+        let mut receipt_mpt_handler = trie.lock().expect("Failed to lock trie");
         let proof = receipt_mpt_handler.get_proof(tx_index)?;
         let proof: Vec<Bytes> = proof.into_iter().map(Bytes::from).collect();
         let receipt = receipt_mpt_handler.get_tx_receipt(tx_index)?;
@@ -140,7 +384,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_transaction() {
-        let client = TransactionClient {};
+        let client = TransactionClient::new();
         let chain_map = get_rpc_urls();
         let url = chain_map.get(&ChainId::EthereumSepolia).unwrap();
         let tx_res = client
@@ -152,12 +396,101 @@ mod tests {
         let mpt = Mpt {
             root: tx_res.mpt_root,
         };
-        mpt.verify_transaction(2, tx_res.proof).unwrap();
+        mpt.verify_transaction(2, &tx_res.tx.0.encoded_2718(), tx_res.proof)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_transaction_count() {
+        let client = TransactionClient::new();
+        let chain_map = get_rpc_urls();
+        let url = chain_map.get(&ChainId::EthereumSepolia).unwrap();
+        let count_res = client
+            .get_transaction_count(url.clone(), 5244634)
+            .await
+            .unwrap();
+        let tx_res = client
+            .get_transaction(url.clone(), 5244634, count_res.count - 1)
+            .await
+            .unwrap();
+        assert_eq!(count_res.mpt_root, tx_res.mpt_root);
+
+        // Verify the exclusion proof
+        let mpt = Mpt {
+            root: count_res.mpt_root,
+        };
+        mpt.verify_transaction_exclusion(count_res.count, count_res.exclusion_proof)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_transaction_location() {
+        let client = TransactionClient::new();
+        let chain_map = get_rpc_urls();
+        let url = chain_map.get(&ChainId::EthereumSepolia).unwrap();
+        let tx_res = client
+            .get_transaction(url.clone(), 5244634, 2)
+            .await
+            .unwrap();
+
+        let location = client
+            .get_transaction_location(url.clone(), *tx_res.tx.0.tx_hash())
+            .await
+            .unwrap();
+        assert_eq!(location, Some((5244634, 2)));
+    }
+
+    #[test]
+    fn test_trie_cache_lru() {
+        let url = Url::parse("http://localhost:8545").unwrap();
+        let key = |block_number: u64| (url.clone(), block_number);
+        let mut cache = TrieCache::new(2);
+        cache.insert(key(1), Arc::new(Mutex::new(1)));
+        cache.insert(key(2), Arc::new(Mutex::new(2)));
+
+        // Using block 1 makes block 2 the least recently used
+        assert_eq!(*cache.get(&key(1)).unwrap().lock().unwrap(), 1);
+        cache.insert(key(3), Arc::new(Mutex::new(3)));
+        assert!(cache.get(&key(2)).is_none());
+        assert!(cache.get(&key(1)).is_some());
+        assert!(cache.get(&key(3)).is_some());
+
+        let mut disabled = TrieCache::new(0);
+        disabled.insert(key(1), Arc::new(Mutex::new(1)));
+        assert!(disabled.get(&key(1)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_transactions_from_cached_trie() {
+        let client = TransactionClient::with_capacity(1);
+        let chain_map = get_rpc_urls();
+        let url = chain_map.get(&ChainId::EthereumSepolia).unwrap();
+        let first = client
+            .get_transaction(url.clone(), 5244634, 0)
+            .await
+            .unwrap();
+        let second = client
+            .get_transaction(url.clone(), 5244634, 2)
+            .await
+            .unwrap();
+        assert_eq!(first.mpt_root, second.mpt_root);
+        assert!(client
+            .tx_tries
+            .lock()
+            .unwrap()
+            .get(&(url.clone(), 5244634))
+            .is_some());
+
+        let mpt = Mpt {
+            root: second.mpt_root,
+        };
+        mpt.verify_transaction(2, &second.tx.0.encoded_2718(), second.proof)
+            .unwrap();
     }
 
     #[tokio::test]
     async fn test_get_receipt() {
-        let client = TransactionClient {};
+        let client = TransactionClient::new();
         let chain_map = get_rpc_urls();
         let url = chain_map.get(&ChainId::EthereumSepolia).unwrap().to_owned();
         let tx_res = client.get_receipt(url, 5244634, 2).await.unwrap();
@@ -166,6 +499,7 @@ mod tests {
         let mpt = Mpt {
             root: tx_res.mpt_root,
         };
-        mpt.verify_receipt(2, tx_res.proof).unwrap();
+        mpt.verify_receipt(2, &tx_res.receipt.0.encoded_2718(), tx_res.proof)
+            .unwrap();
     }
 }
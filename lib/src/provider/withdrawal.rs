@@ -0,0 +1,131 @@
+use crate::mpt::ordered_trie_with_proof;
+use alloy_eips::{eip4895::Withdrawal, BlockNumberOrTag};
+use alloy_primitives::{Bytes, B256};
+use alloy_rpc_client::{ClientBuilder, ReqwestClient};
+use serde::Deserialize;
+use url::Url;
+
+/// The subset of an `eth_getBlockByNumber` response that carries the block withdrawals.
+#[derive(Deserialize, Debug)]
+struct BlockWithdrawalsRpc {
+    withdrawals: Option<Vec<Withdrawal>>,
+}
+
+/// Represents a response containing withdrawal details, including the MPT root,
+/// the withdrawal data, and the proof elements.
+#[derive(Debug)]
+pub struct WithdrawalResponse {
+    /// The index of the withdrawal within the block.
+    pub withdrawal_index: u64,
+    /// The Merkle Patricia Trie root hash for the withdrawals.
+    pub mpt_root: B256,
+    /// The withdrawal data.
+    pub withdrawal: Withdrawal,
+    /// The proof elements for the withdrawal in the MPT.
+    pub proof: Vec<Bytes>,
+}
+
+/// A provider for fetching withdrawals along with their proofs.
+#[derive(Debug)]
+pub struct WithdrawalProvider {
+    /// The RPC client.
+    pub client: ReqwestClient,
+}
+
+impl WithdrawalProvider {
+    /// Creates a new [`WithdrawalProvider`] instance with the given RPC URL
+    pub fn new(rpc_url: Url) -> Self {
+        Self {
+            client: ClientBuilder::default().http(rpc_url),
+        }
+    }
+
+    /// Fetches a withdrawal from the specified block and withdrawal index,
+    /// rebuilding the withdrawals Merkle Patricia Trie (MPT) from the block body to retrieve the proof.
+    ///
+    /// Returns `None` if the block has no withdrawal at the given index, which includes
+    /// every block produced before Shanghai.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::Url;
+    /// use hdp_lib::WithdrawalProvider;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let provider = WithdrawalProvider::new(Url::parse("https://YOUR_RPC_URL").unwrap());
+    ///     match provider.get_withdrawal(5244634, 2).await {
+    ///         Ok(response) => println!("{:?}", response),
+    ///         Err(e) => eprintln!("Error fetching withdrawal: {:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn get_withdrawal(
+        &self,
+        block_number: u64,
+        withdrawal_index: u64,
+    ) -> Result<Option<WithdrawalResponse>, alloy_transport::TransportError> {
+        let mut batch = self.client.new_batch();
+        let block_fut: alloy_rpc_client::Waiter<Option<BlockWithdrawalsRpc>> = batch.add_call(
+            "eth_getBlockByNumber",
+            &(BlockNumberOrTag::from(block_number), false),
+        )?;
+        batch.send().await?;
+        let withdrawals = match block_fut.await?.and_then(|block| block.withdrawals) {
+            Some(withdrawals) => withdrawals,
+            None => return Ok(None),
+        };
+        let withdrawal = match withdrawals.get(withdrawal_index as usize) {
+            Some(withdrawal) => *withdrawal,
+            None => return Ok(None),
+        };
+
+        let encoded: Vec<Bytes> = withdrawals
+            .iter()
+            .map(|withdrawal| alloy_rlp::encode(withdrawal).into())
+            .collect();
+        let (mpt_root, proof) = ordered_trie_with_proof(&encoded, withdrawal_index);
+
+        Ok(Some(WithdrawalResponse {
+            withdrawal_index,
+            mpt_root,
+            withdrawal,
+            proof,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{chain::ChainId, header::IndexerClient, mpt::Mpt, utils::get_rpc_urls};
+    use alloy_consensus::Header;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_withdrawal() {
+        let chain_map = get_rpc_urls();
+        let url = chain_map.get(&ChainId::EthereumSepolia).unwrap().to_owned();
+        let client = IndexerClient::default();
+        let indexer_rpc = client.get_header(5244634).await.unwrap();
+        let header: Header = indexer_rpc
+            .proofs
+            .first()
+            .unwrap()
+            .rlp_block_header
+            .clone()
+            .into();
+
+        let provider = WithdrawalProvider::new(url);
+        let res = provider.get_withdrawal(5244634, 2).await.unwrap().unwrap();
+        assert_eq!(Some(res.mpt_root), header.withdrawals_root);
+
+        // Verify the withdrawal proof
+        let mpt = Mpt {
+            root: header.withdrawals_root.unwrap(),
+        };
+        mpt.verify_withdrawal(2, &alloy_rlp::encode(res.withdrawal), res.proof)
+            .unwrap();
+    }
+}
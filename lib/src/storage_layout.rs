@@ -0,0 +1,513 @@
+use crate::{
+    chain::ChainId,
+    memorizer::{MemorizerError, StorageKey, StorageMemorizer},
+};
+use alloy_primitives::{keccak256, Address, Bytes, FixedBytes, B256, I256, U256};
+
+/// A storage slot of a contract, following the Solidity storage layout.
+///
+/// Slots are derived the same way `solc` lays out state variables:
+/// - `mapping[key]` lives at `keccak256(key . slot)`.
+/// - The elements of a dynamic array, and the data of a long `string` or `bytes`, start at `keccak256(slot)`.
+/// - Struct members and fixed-size array elements follow the base slot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct StorageSlot(pub U256);
+
+impl StorageSlot {
+    /// Creates a new [`StorageSlot`] from the slot number of a state variable.
+    pub fn new(slot: u64) -> Self {
+        Self(U256::from(slot))
+    }
+
+    /// Returns the slot of `mapping[key]`, where `self` is the slot of the mapping.
+    ///
+    /// Nested mappings are reached by chaining calls, e.g. `allowance[owner][spender]`
+    /// is `slot.mapping(&owner).mapping(&spender)`.
+    pub fn mapping<K: MappingKey + ?Sized>(self, key: &K) -> Self {
+        let mut data = key.mapping_key_bytes();
+        data.extend_from_slice(&self.0.to_be_bytes::<32>());
+        Self(keccak256(data).into())
+    }
+
+    /// Returns the first slot of the elements of a dynamic array, or of the data of a long
+    /// `string` or `bytes`, where `self` is the slot holding the length.
+    pub fn data(self) -> Self {
+        Self(keccak256(self.0.to_be_bytes::<32>()).into())
+    }
+
+    /// Returns the slot `offset` slots after `self`, as for a struct member or a
+    /// fixed-size array element.
+    pub fn offset(self, offset: u64) -> Self {
+        Self(self.0.wrapping_add(U256::from(offset)))
+    }
+
+    /// Returns the location of the element at `index` of a dynamic array, where `self` is the
+    /// slot of the array and `element_size` the size of one element in bytes.
+    ///
+    /// Elements of at most 16 bytes share slots, as in `uint8[]`.
+    pub fn array_element(self, index: u64, element_size: usize) -> StorageLocation {
+        self.data().element(index, element_size)
+    }
+
+    /// Returns the location of the element at `index` of an array stored from `self` on,
+    /// where `element_size` is the size of one element in bytes.
+    ///
+    /// This is the layout of a fixed-size array starting at `self`.
+    ///
+    /// # Panics
+    /// Panics if `element_size` is zero.
+    pub fn element(self, index: u64, element_size: usize) -> StorageLocation {
+        assert!(
+            element_size > 0,
+            "array element size must be at least one byte"
+        );
+        if element_size <= 16 {
+            let per_slot = (32 / element_size) as u64;
+            StorageLocation {
+                slot: self.offset(index / per_slot),
+                offset: (index % per_slot) as usize * element_size,
+                size: element_size,
+            }
+        } else {
+            let slots_per_element = element_size.div_ceil(32) as u64;
+            StorageLocation {
+                slot: self.offset(index * slots_per_element),
+                offset: 0,
+                size: element_size.min(32),
+            }
+        }
+    }
+
+    /// Returns the location of a value of type `T` alone in the slot, at its lowest-order bytes.
+    pub fn value<T: StorageValue>(self) -> StorageLocation {
+        self.packed(0, T::SIZE)
+    }
+
+    /// Returns the location of a value of `size` bytes at byte `offset` within the slot.
+    ///
+    /// # Panics
+    /// Panics if the value does not fit in the slot.
+    pub fn packed(self, offset: usize, size: usize) -> StorageLocation {
+        assert!(
+            offset + size <= 32,
+            "packed value of {size} bytes at offset {offset} overflows the slot"
+        );
+        StorageLocation {
+            slot: self,
+            offset,
+            size,
+        }
+    }
+
+    /// Creates the [`StorageKey`] of this slot for the account at `address`.
+    pub fn storage_key(self, chain_id: ChainId, block_number: u64, address: Address) -> StorageKey {
+        StorageKey {
+            chain_id,
+            block_number,
+            address,
+            storage_slot: self.into(),
+        }
+    }
+}
+
+impl From<u64> for StorageSlot {
+    fn from(slot: u64) -> Self {
+        Self::new(slot)
+    }
+}
+
+impl From<U256> for StorageSlot {
+    fn from(slot: U256) -> Self {
+        Self(slot)
+    }
+}
+
+impl From<B256> for StorageSlot {
+    fn from(slot: B256) -> Self {
+        Self(slot.into())
+    }
+}
+
+impl From<StorageSlot> for B256 {
+    fn from(slot: StorageSlot) -> Self {
+        slot.0.into()
+    }
+}
+
+/// The location of a value within a storage slot.
+///
+/// Values smaller than 32 bytes are packed together: `offset` counts bytes from the
+/// lowest-order end of the slot, as in the `offset` of a `solc` storage layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StorageLocation {
+    /// Slot holding the value.
+    pub slot: StorageSlot,
+    /// Offset of the value in bytes, from the lowest-order end of the slot.
+    pub offset: usize,
+    /// Size of the value in bytes.
+    pub size: usize,
+}
+
+impl StorageLocation {
+    /// Creates a new [`StorageLocation`] spanning the whole slot.
+    ///
+    /// Values smaller than a slot are located with [`StorageSlot::value`] instead.
+    pub fn new(slot: StorageSlot) -> Self {
+        Self {
+            slot,
+            offset: 0,
+            size: 32,
+        }
+    }
+
+    /// Extracts the bytes of this location from the value of its slot.
+    pub fn extract(&self, word: U256) -> U256 {
+        let shifted = word >> (self.offset * 8);
+        if self.size >= 32 {
+            shifted
+        } else {
+            shifted & ((U256::from(1) << (self.size * 8)) - U256::from(1))
+        }
+    }
+
+    /// Decodes the value at this location from the value of its slot.
+    ///
+    /// Fails if the location is larger than `T`, whose value could not hold the extracted bytes.
+    /// Signed values are sign-extended from the size of the location.
+    pub fn decode<T: StorageValue>(&self, word: U256) -> Result<T, MemorizerError> {
+        if self.size > T::SIZE {
+            return Err(MemorizerError::StorageSizeMismatch(self.size, T::SIZE));
+        }
+        let mut word = self.extract(word);
+        if T::SIGNED && self.size < 32 && word.bit(self.size * 8 - 1) {
+            word |= U256::MAX << (self.size * 8);
+        }
+        Ok(T::from_word(word))
+    }
+
+    /// Reads and decodes the value at this location for the account at `address`.
+    pub fn read<T: StorageValue, M: StorageMemorizer>(
+        &self,
+        memorizer: &mut M,
+        chain_id: ChainId,
+        block_number: u64,
+        address: Address,
+    ) -> Result<T, MemorizerError> {
+        let word = memorizer.get_storage(self.slot.storage_key(chain_id, block_number, address))?;
+        self.decode(word)
+    }
+}
+
+impl From<StorageSlot> for StorageLocation {
+    fn from(slot: StorageSlot) -> Self {
+        Self::new(slot)
+    }
+}
+
+/// A type usable as the key of a Solidity mapping.
+pub trait MappingKey {
+    /// Returns the encoding of the key hashed together with the mapping slot.
+    ///
+    /// Value types are left-padded to 32 bytes, while `string` and `bytes` keys are hashed unpadded.
+    fn mapping_key_bytes(&self) -> Vec<u8>;
+}
+
+impl<T: MappingKey + ?Sized> MappingKey for &T {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        (**self).mapping_key_bytes()
+    }
+}
+
+impl MappingKey for Address {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        self.into_word().to_vec()
+    }
+}
+
+impl MappingKey for U256 {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        self.to_be_bytes_vec()
+    }
+}
+
+/// `bytesN` keys are left-aligned, as they are in memory.
+impl<const N: usize> MappingKey for FixedBytes<N> {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.to_vec();
+        bytes.resize(bytes.len().max(32), 0);
+        bytes
+    }
+}
+
+impl MappingKey for I256 {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        self.into_raw().to_be_bytes_vec()
+    }
+}
+
+impl MappingKey for u64 {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        U256::from(*self).to_be_bytes_vec()
+    }
+}
+
+impl MappingKey for u128 {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        U256::from(*self).to_be_bytes_vec()
+    }
+}
+
+macro_rules! impl_mapping_key_int {
+    ($($ty:ty),*) => {
+        $(
+            impl MappingKey for $ty {
+                fn mapping_key_bytes(&self) -> Vec<u8> {
+                    I256::try_from(*self).unwrap().into_raw().to_be_bytes_vec()
+                }
+            }
+        )*
+    };
+}
+
+impl_mapping_key_int!(i8, i16, i32, i64, i128);
+
+impl MappingKey for bool {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        U256::from(*self as u8).to_be_bytes_vec()
+    }
+}
+
+impl MappingKey for str {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl MappingKey for String {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl MappingKey for [u8] {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+impl MappingKey for Bytes {
+    fn mapping_key_bytes(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+/// A value type that can be decoded from the bytes of a [`StorageLocation`].
+pub trait StorageValue: Sized {
+    /// Size of the value in storage, in bytes.
+    const SIZE: usize;
+
+    /// Whether the value is a two's complement integer, sign-extended when decoded.
+    const SIGNED: bool = false;
+
+    /// Decodes the value from the right-aligned bytes extracted from its slot.
+    fn from_word(word: U256) -> Self;
+}
+
+impl StorageValue for U256 {
+    const SIZE: usize = 32;
+
+    fn from_word(word: U256) -> Self {
+        word
+    }
+}
+
+/// `bytesN` values occupy `N` bytes of the slot.
+impl<const N: usize> StorageValue for FixedBytes<N> {
+    const SIZE: usize = N;
+
+    fn from_word(word: U256) -> Self {
+        Self::from_slice(&word.to_be_bytes::<32>()[32 - N..])
+    }
+}
+
+impl StorageValue for I256 {
+    const SIZE: usize = 32;
+    const SIGNED: bool = true;
+
+    fn from_word(word: U256) -> Self {
+        Self::from_raw(word)
+    }
+}
+
+impl StorageValue for Address {
+    const SIZE: usize = 20;
+
+    fn from_word(word: U256) -> Self {
+        Address::from_word(word.into())
+    }
+}
+
+impl StorageValue for bool {
+    const SIZE: usize = 1;
+
+    fn from_word(word: U256) -> Self {
+        !word.is_zero()
+    }
+}
+
+macro_rules! impl_storage_value_uint {
+    ($($ty:ty),*) => {
+        $(
+            impl StorageValue for $ty {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+
+                fn from_word(word: U256) -> Self {
+                    word.to::<$ty>()
+                }
+            }
+        )*
+    };
+}
+
+impl_storage_value_uint!(u8, u16, u32, u64, u128);
+
+macro_rules! impl_storage_value_int {
+    ($($ty:ty),*) => {
+        $(
+            impl StorageValue for $ty {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+                const SIGNED: bool = true;
+
+                fn from_word(word: U256) -> Self {
+                    word.wrapping_to::<$ty>()
+                }
+            }
+        )*
+    };
+}
+
+impl_storage_value_int!(i8, i16, i32, i64, i128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256, uint};
+
+    #[test]
+    fn test_mapping_slot() {
+        let owner = address!("75cec1db9dceb703200eaa6595f66885c962b920");
+        let spender = address!("7f2c6f930306d3aa736b3a6c6a98f512f74036d4");
+
+        assert_eq!(
+            B256::from(StorageSlot::new(0).mapping(&Address::ZERO)),
+            b256!("ad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5")
+        );
+        assert_eq!(
+            B256::from(StorageSlot::new(2).mapping(&owner)),
+            b256!("54e19759af56025d31202309ada415f7142bbe8ef3070797094d9e369053c62c")
+        );
+        assert_eq!(
+            B256::from(StorageSlot::new(1).mapping("hello")),
+            b256!("8404bb4d805e9ca2bd5dd5c43a107e935c8ec393caa7851b353b3192cd5379ae")
+        );
+        assert_eq!(
+            B256::from(StorageSlot::new(3).mapping(&owner).mapping(&spender)),
+            b256!("78535358d635e151f42eb742d1a644c8787dbb4fa2509af7182f5f14299a5f4e")
+        );
+    }
+
+    #[test]
+    fn test_array_element_location() {
+        let data = b256!("036b6384b5eca791c62761152d0c79bb0604c104a5fb6f4eb0703f3154bb3db0");
+        assert_eq!(B256::from(StorageSlot::new(5).data()), data);
+
+        let location = StorageSlot::new(5).array_element(3, 16);
+        assert_eq!(location.slot, StorageSlot::from(data).offset(1));
+        assert_eq!(location.offset, 16);
+
+        let location = StorageSlot::new(5).array_element(3, 64);
+        assert_eq!(
+            location,
+            StorageLocation::new(StorageSlot::from(data).offset(6))
+        );
+    }
+
+    #[test]
+    fn test_packed_decode() {
+        // slot 0: address owner (offset 0), bool paused (offset 20), uint64 nonce (offset 21)
+        let word = uint!(0x000000000000000000002a0175cec1db9dceb703200eaa6595f66885c962b920_U256);
+        let slot = StorageSlot::new(0);
+
+        let owner: Address = slot.packed(0, Address::SIZE).decode(word).unwrap();
+        let paused: bool = slot.packed(20, bool::SIZE).decode(word).unwrap();
+        let nonce: u64 = slot.packed(21, u64::SIZE).decode(word).unwrap();
+        assert_eq!(owner, address!("75cec1db9dceb703200eaa6595f66885c962b920"));
+        assert!(paused);
+        assert_eq!(nonce, 42);
+    }
+
+    #[test]
+    fn test_decode_size_mismatch() {
+        let location = StorageLocation::new(StorageSlot::new(0));
+        assert!(matches!(
+            location.decode::<u8>(U256::MAX),
+            Err(MemorizerError::StorageSizeMismatch(32, 1))
+        ));
+        assert_eq!(location.decode::<U256>(U256::MAX).unwrap(), U256::MAX);
+    }
+
+    #[test]
+    fn test_slot_value() {
+        let owner = address!("75cec1db9dceb703200eaa6595f66885c962b920");
+        let word = U256::from_be_slice(owner.as_slice());
+        let location = StorageSlot::new(3).value::<Address>();
+        assert_eq!(location, StorageSlot::new(3).packed(0, 20));
+        assert_eq!(location.decode::<Address>(word).unwrap(), owner);
+    }
+
+    #[test]
+    fn test_decode_typed_values() {
+        // int24 -2 at offset 0, bytes4 0x01ffc9a7 at offset 3
+        let word = uint!(0x01ffc9a7fffffe_U256);
+        let slot = StorageSlot::new(0);
+        assert_eq!(slot.packed(0, 3).decode::<i32>(word).unwrap(), -2);
+        assert_eq!(slot.packed(0, 3).decode::<u32>(word).unwrap(), 0xfffffe);
+        assert_eq!(
+            slot.packed(3, 4).decode::<FixedBytes<4>>(word).unwrap(),
+            FixedBytes::from([0x01, 0xff, 0xc9, 0xa7])
+        );
+        assert_eq!(
+            slot.value::<I256>().decode::<I256>(U256::MAX).unwrap(),
+            I256::MINUS_ONE
+        );
+        assert_eq!(
+            slot.value::<i8>().decode::<i8>(U256::from(0x7f)).unwrap(),
+            127
+        );
+    }
+
+    #[test]
+    fn test_typed_mapping_keys() {
+        let mut expected = vec![0x01, 0xff, 0xc9, 0xa7];
+        expected.resize(32, 0);
+        assert_eq!(
+            FixedBytes::<4>::from([0x01, 0xff, 0xc9, 0xa7]).mapping_key_bytes(),
+            expected
+        );
+        assert_eq!((-1i64).mapping_key_bytes(), vec![0xff; 32]);
+        assert_eq!(5i8.mapping_key_bytes(), U256::from(5).to_be_bytes_vec());
+        assert_eq!(I256::MINUS_ONE.mapping_key_bytes(), vec![0xff; 32]);
+    }
+
+    #[test]
+    #[should_panic(expected = "packed value of 8 bytes at offset 30 overflows the slot")]
+    fn test_packed_overflow() {
+        StorageSlot::new(0).packed(30, 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "array element size must be at least one byte")]
+    fn test_zero_size_element() {
+        StorageSlot::new(0).element(1, 0);
+    }
+}
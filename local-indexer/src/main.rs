@@ -0,0 +1,214 @@
+//! A stand-in for the Herodotus Indexer, serving the `/accumulators/proofs` API from a
+//! header accumulator built locally.
+//!
+//! Headers are read from a file of hex-encoded RLP headers, one per line, or from the
+//! node at `--rpc-url`. Point online mode at the server with
+//! `INDEXER_RPC_URL=http://localhost:3030/accumulators/proofs`.
+
+use alloy_primitives::{Bytes, U256};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use clap::Parser;
+use hdp_lib::{
+    accumulator::{AccumulatorError, LocalAccumulator},
+    block::BlockProvider,
+    header::IndexerResponse,
+    mmr::{KeccakHasher, MmrHasher},
+};
+use std::{error::Error, path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
+use url::Url;
+
+/// The arguments for the command.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// File holding the accumulator, created if missing.
+    #[clap(long, default_value = "accumulator.bin")]
+    accumulator: PathBuf,
+
+    /// ID of the MMR when creating the accumulator.
+    #[clap(long, default_value = "1")]
+    mmr_id: u64,
+
+    /// File of hex-encoded RLP headers, one per line, appended on startup.
+    #[clap(long)]
+    headers: Option<PathBuf>,
+
+    /// ID of the chain whose headers are accumulated, the only `accumulates_chain` served.
+    #[clap(long, default_value = "11155111")]
+    chain_id: u128,
+
+    /// RPC URL of the node whose headers are appended.
+    #[clap(long, env = "LOCAL_RPC_URL")]
+    rpc_url: Option<Url>,
+
+    /// Seconds between two syncs with the node. The node is only synced on startup if unset.
+    #[clap(long)]
+    poll_interval: Option<u64>,
+
+    /// Port to listen on.
+    #[clap(long, default_value = "3030")]
+    port: u16,
+}
+
+type SharedAccumulator = Arc<RwLock<LocalAccumulator>>;
+
+/// The state shared by the request handlers.
+#[derive(Clone)]
+struct Server {
+    accumulator: SharedAccumulator,
+    /// ID of the chain whose headers are accumulated.
+    chain_id: u128,
+}
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+#[tokio::main]
+async fn main() -> Result<(), BoxError> {
+    let args = Args::parse();
+
+    let mut accumulator = LocalAccumulator::open(&args.accumulator, U256::from(args.mmr_id))?;
+    if let Some(headers) = &args.headers {
+        let count = accumulator.import_file(headers)?;
+        println!("Appended {count} headers from {headers:?}");
+    }
+    if let Some(rpc_url) = &args.rpc_url {
+        let count = sync(&mut accumulator, rpc_url.clone()).await?;
+        println!("Appended {count} headers from {rpc_url}");
+    }
+    accumulator.save()?;
+    println!(
+        "Accumulator {:#x} holds {} elements",
+        accumulator.mmr_id(),
+        accumulator.mmr_size()
+    );
+
+    let accumulator = Arc::new(RwLock::new(accumulator));
+    if let (Some(rpc_url), Some(poll_interval)) = (args.rpc_url, args.poll_interval) {
+        tokio::spawn(poll(
+            accumulator.clone(),
+            rpc_url,
+            Duration::from_secs(poll_interval),
+        ));
+    }
+
+    let app = Router::new()
+        .route("/accumulators/proofs", get(proofs))
+        .with_state(Server {
+            accumulator,
+            chain_id: args.chain_id,
+        });
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", args.port)).await?;
+    println!("Serving /accumulators/proofs on port {}", args.port);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// Appends the headers of the node up to its latest block.
+async fn sync(accumulator: &mut LocalAccumulator, rpc_url: Url) -> Result<usize, BoxError> {
+    let latest_block = BlockProvider::new(rpc_url.clone())
+        .get_latest_block_number()
+        .await?;
+    Ok(accumulator.sync(rpc_url, latest_block).await?)
+}
+
+/// Keeps appending the new headers of the node, saving the accumulator after every sync.
+///
+/// Headers are fetched without holding the lock, so proofs keep being served during a sync.
+async fn poll(accumulator: SharedAccumulator, rpc_url: Url, poll_interval: Duration) {
+    let mut interval = tokio::time::interval(poll_interval);
+    loop {
+        interval.tick().await;
+        let from_block = accumulator.read().await.next_block_number();
+        let headers = match fetch_headers(rpc_url.clone(), from_block).await {
+            Ok(headers) if headers.is_empty() => continue,
+            Ok(headers) => headers,
+            Err(e) => {
+                eprintln!("Error syncing headers: {e}");
+                continue;
+            }
+        };
+
+        let mut accumulator = accumulator.write().await;
+        if accumulator.next_block_number() != from_block {
+            // Appended by someone else meanwhile, the next sync starts from the new tip.
+            continue;
+        }
+        if let Err(e) = headers
+            .iter()
+            .try_for_each(|rlp| accumulator.append_rlp(rlp).map(|_| ()))
+        {
+            eprintln!("Error syncing headers: {e}");
+            continue;
+        }
+        match accumulator.save() {
+            Ok(()) => println!("Appended {} headers from {rpc_url}", headers.len()),
+            Err(e) => eprintln!("Error saving accumulator: {e}"),
+        }
+    }
+}
+
+/// Fetches the raw headers of the node from `from_block` up to its latest block.
+async fn fetch_headers(rpc_url: Url, from_block: u64) -> Result<Vec<Bytes>, BoxError> {
+    let provider = BlockProvider::new(rpc_url);
+    let latest_block = provider.get_latest_block_number().await?;
+    if from_block > latest_block {
+        return Ok(Vec::new());
+    }
+    Ok(provider.get_raw_headers(from_block..=latest_block).await?)
+}
+
+/// Serves the headers of the requested blocks with their proofs against the current MMR,
+/// in the shape of the Indexer API.
+///
+/// Requests must accumulate the configured chain. The MMR is not deployed on any chain, so
+/// `deployed_on_chain` is not checked.
+async fn proofs(
+    State(server): State<Server>,
+    Query(params): Query<Vec<(String, String)>>,
+) -> Result<Json<IndexerResponse>, (StatusCode, String)> {
+    let mut block_numbers = Vec::new();
+    let mut accumulates_chain = None;
+    for (key, value) in params {
+        match key.as_str() {
+            "accumulates_chain" => accumulates_chain = Some(value),
+            "block_numbers" => block_numbers.push(value.parse::<u64>().map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid block number `{value}`: {e}"),
+                )
+            })?),
+            "hashing_function" if value != KeccakHasher::NAME => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    format!("Unsupported hashing function `{value}`"),
+                ))
+            }
+            _ => {}
+        }
+    }
+    let chain_id = server.chain_id.to_string();
+    if accumulates_chain.as_deref() != Some(chain_id.as_str()) {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!(
+                "No accumulator for chain `{}`, only chain {chain_id} is accumulated",
+                accumulates_chain.unwrap_or_default()
+            ),
+        ));
+    }
+
+    let accumulator = server.accumulator.read().await;
+    let indexer_rpc = accumulator
+        .indexer_rpc(&block_numbers)
+        .map_err(|e| match e {
+            AccumulatorError::MissingHeader(_) => (StatusCode::NOT_FOUND, e.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+    Ok(Json(IndexerResponse::new(vec![indexer_rpc])))
+}